@@ -0,0 +1,384 @@
+//! Derive macros for `discord-async`, re-exported by it with the `derive` feature
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+	parse_macro_input, Attribute, Data, DataEnum, DeriveInput, Error, Expr, ExprArray, Fields,
+	GenericArgument, Lit, LitStr, PathArguments, Result, Type,
+};
+
+/// Implements `CommandArgs` and `Command` of `discord_async::interaction`.
+///
+/// A struct with named fields is a command with an option per field, an `Option<T>`
+/// field is an optional option. An enum is a command with a subcommand per variant,
+/// unit variants are subcommands without options and single field tuple variants
+/// are subcommands (for structs) or subcommand groups (for enums).
+///
+/// Descriptions are taken from doc comments, or from a `description` attribute.
+///
+/// ```ignore
+/// /// Roll some dice
+/// #[derive(Command)]
+/// struct Roll {
+///     /// Number of sides
+///     #[option(min = 2, max = 100)]
+///     sides: i64,
+///     /// Color of the dice
+///     #[option(choice(name = "Red", value = "red"), choice(name = "Blue", value = "blue"))]
+///     color: Option<String>,
+/// }
+/// ```
+///
+/// Attributes:
+/// - `#[command(name = "...", description = "...")]` on the type or a variant
+/// - `#[option(rename = "...", description = "...", min = .., max = .., min_length = ..,
+///   max_length = .., channel_types = [..], choice(name = "...", value = ..), autocomplete)]`
+///   on a field
+#[proc_macro_derive(Command, attributes(command, option))]
+pub fn derive_command(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(input)
+		.unwrap_or_else(Error::into_compile_error)
+		.into()
+}
+
+const MAX_NAME: usize = 32;
+const MAX_DESCRIPTION: usize = 100;
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+	let krate = quote!(::discord_async::interaction);
+	let ident = &input.ident;
+	let attrs = CommandAttrs::parse(&input.attrs)?;
+	let name = name(attrs.name, &ident.to_string(), ident.span())?;
+	let description = description(&input.attrs, attrs.description, ident.span())?;
+
+	let (nested_type, options, parse) = match &input.data {
+		Data::Struct(data) => {
+			let (options, parse) = expand_fields(&data.fields)?;
+			(quote!(SubCommand), options, parse)
+		}
+		Data::Enum(data) => {
+			let (options, parse) = expand_variants(data)?;
+			(quote!(SubCommandGroup), options, parse)
+		}
+		Data::Union(_) => return Err(Error::new_spanned(ident, "unions are not supported")),
+	};
+
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics #krate::CommandArgs for #ident #ty_generics #where_clause {
+			const NESTED_TYPE: #krate::OptionType = #krate::OptionType::#nested_type;
+
+			fn options() -> ::std::vec::Vec<#krate::OptionSpec> {
+				::std::vec![#(#options),*]
+			}
+
+			fn from_options(
+				options: #krate::CommandOptions<'_>,
+			) -> ::std::result::Result<Self, #krate::ParseError> {
+				#parse
+			}
+		}
+
+		impl #impl_generics #krate::Command for #ident #ty_generics #where_clause {
+			const NAME: &'static str = #name;
+			const DESCRIPTION: &'static str = #description;
+		}
+	})
+}
+
+/// Option specs and parser of a struct
+fn expand_fields(fields: &Fields) -> Result<(Vec<TokenStream2>, TokenStream2)> {
+	let krate = quote!(::discord_async::interaction);
+	let fields = match fields {
+		Fields::Named(fields) => &fields.named,
+		Fields::Unit => return Ok((Vec::new(), quote!(let _ = options; Ok(Self)))),
+		Fields::Unnamed(_) => {
+			return Err(Error::new_spanned(
+				fields,
+				"only structs with named fields are supported",
+			))
+		}
+	};
+
+	let mut options = Vec::new();
+	let mut values = Vec::new();
+	for field in fields {
+		let ident = field.ident.as_ref().expect("named field");
+		let attrs = OptionAttrs::parse(&field.attrs)?;
+		let ident_name = ident.to_string();
+		let name = name(
+			attrs.rename,
+			ident_name.trim_start_matches("r#"),
+			ident.span(),
+		)?;
+		let description = description(&field.attrs, attrs.description, ident.span())?;
+		if attrs.choices && attrs.autocomplete {
+			return Err(Error::new(
+				ident.span(),
+				"options can't have both choices and autocomplete",
+			));
+		}
+		let (ty, required) = match option_inner(&field.ty) {
+			Some(ty) => (ty, false),
+			None => (&field.ty, true),
+		};
+
+		let modifiers = attrs.modifiers;
+		options.push(quote! {
+			#krate::OptionSpec::new::<#ty>(#name, #description)
+				.required(#required)
+				#(#modifiers)*
+		});
+		values.push(match required {
+			true => quote!(#ident: options.require::<#ty>(#name)?),
+			false => quote!(#ident: options.get::<#ty>(#name)?),
+		});
+	}
+
+	Ok((options, quote!(Ok(Self { #(#values),* }))))
+}
+
+/// Option specs and parser of an enum
+fn expand_variants(data: &DataEnum) -> Result<(Vec<TokenStream2>, TokenStream2)> {
+	let krate = quote!(::discord_async::interaction);
+	let mut options = Vec::new();
+	let mut arms = Vec::new();
+	for variant in &data.variants {
+		let ident = &variant.ident;
+		let attrs = CommandAttrs::parse(&variant.attrs)?;
+		let name = name(attrs.name, &ident.to_string(), ident.span())?;
+		let description = description(&variant.attrs, attrs.description, ident.span());
+
+		match &variant.fields {
+			Fields::Unit => {
+				let description = description?;
+				options.push(quote!(#krate::OptionSpec::subcommand(#name, #description)));
+				arms.push(quote!(#name => Ok(Self::#ident)));
+			}
+			Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+				let ty = &fields.unnamed[0].ty;
+				// Fall back to the description of the nested command
+				let description = match description {
+					Ok(description) => quote!(#description),
+					Err(_) => quote!(<#ty as #krate::Command>::DESCRIPTION),
+				};
+				options.push(quote!(#krate::OptionSpec::nested::<#ty>(#name, #description)));
+				arms.push(quote! {
+					#name => Ok(Self::#ident(
+						<#ty as #krate::CommandArgs>::from_options(options)?
+					))
+				});
+			}
+			_ => {
+				return Err(Error::new_spanned(
+					variant,
+					"variants must be unit variants or have a single unnamed field",
+				))
+			}
+		}
+	}
+
+	let parse = quote! {
+		let (name, options) = options.subcommand()?;
+		let _ = options;
+		match name {
+			#(#arms,)*
+			_ => Err(#krate::ParseError::UnknownSubcommand(name.to_owned())),
+		}
+	};
+	Ok((options, parse))
+}
+
+#[derive(Default)]
+struct CommandAttrs {
+	name: Option<LitStr>,
+	description: Option<LitStr>,
+}
+
+impl CommandAttrs {
+	fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut parsed = Self::default();
+		for attr in attrs.iter().filter(|a| a.path().is_ident("command")) {
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("name") {
+					parsed.name = Some(meta.value()?.parse()?);
+				} else if meta.path.is_ident("description") {
+					parsed.description = Some(meta.value()?.parse()?);
+				} else {
+					return Err(meta.error("unknown command attribute"));
+				}
+				Ok(())
+			})?;
+		}
+		Ok(parsed)
+	}
+}
+
+#[derive(Default)]
+struct OptionAttrs {
+	rename: Option<LitStr>,
+	description: Option<LitStr>,
+	/// Calls on the `OptionSpec`, e.g. `.min_value(2)`
+	modifiers: Vec<TokenStream2>,
+	choices: bool,
+	autocomplete: bool,
+}
+
+impl OptionAttrs {
+	fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut parsed = Self::default();
+		for attr in attrs.iter().filter(|a| a.path().is_ident("option")) {
+			attr.parse_nested_meta(|meta| {
+				let path = &meta.path;
+				if path.is_ident("rename") {
+					parsed.rename = Some(meta.value()?.parse()?);
+				} else if path.is_ident("description") {
+					parsed.description = Some(meta.value()?.parse()?);
+				} else if path.is_ident("min") {
+					let min: Expr = meta.value()?.parse()?;
+					parsed.modifiers.push(quote!(.min_value(#min)));
+				} else if path.is_ident("max") {
+					let max: Expr = meta.value()?.parse()?;
+					parsed.modifiers.push(quote!(.max_value(#max)));
+				} else if path.is_ident("min_length") {
+					let min: Expr = meta.value()?.parse()?;
+					parsed.modifiers.push(quote!(.min_length(#min)));
+				} else if path.is_ident("max_length") {
+					let max: Expr = meta.value()?.parse()?;
+					parsed.modifiers.push(quote!(.max_length(#max)));
+				} else if path.is_ident("channel_types") {
+					let types: ExprArray = meta.value()?.parse()?;
+					let types = types.elems.iter();
+					parsed.modifiers.push(quote!(.channel_types([#(#types),*])));
+				} else if path.is_ident("autocomplete") {
+					parsed.autocomplete = true;
+					parsed.modifiers.push(quote!(.autocomplete(true)));
+				} else if path.is_ident("choice") {
+					parsed.choices = true;
+					let mut name: Option<LitStr> = None;
+					let mut value: Option<Lit> = None;
+					meta.parse_nested_meta(|meta| {
+						if meta.path.is_ident("name") {
+							name = Some(meta.value()?.parse()?);
+						} else if meta.path.is_ident("value") {
+							value = Some(meta.value()?.parse()?);
+						} else {
+							return Err(meta.error("expected `name` or `value`"));
+						}
+						Ok(())
+					})?;
+					match (name, value) {
+						(Some(name), Some(value)) => {
+							parsed.modifiers.push(quote!(.choice(#name, #value)))
+						}
+						_ => return Err(meta.error("a choice needs a `name` and a `value`")),
+					}
+				} else {
+					return Err(meta.error("unknown option attribute"));
+				}
+				Ok(())
+			})?;
+		}
+		Ok(parsed)
+	}
+}
+
+/// Explicit name, or the kebab-cased identifier. Names must match `^[-_\p{L}\p{N}]{1,32}$`
+/// and be lowercase
+fn name(name: Option<LitStr>, ident: &str, span: Span) -> Result<LitStr> {
+	let name = match name {
+		Some(name) => name,
+		None => LitStr::new(&kebab_case(ident), span),
+	};
+	let value = name.value();
+	if value.is_empty() || value.chars().count() > MAX_NAME {
+		return Err(Error::new(name.span(), "names must be 1 to 32 characters"));
+	}
+	if value.chars().any(char::is_uppercase) {
+		return Err(Error::new(name.span(), "names must be lowercase"));
+	}
+	if !value
+		.chars()
+		.all(|c| c == '-' || c == '_' || c.is_alphanumeric())
+	{
+		return Err(Error::new(
+			name.span(),
+			"names can only contain letters, numbers, `-` and `_`",
+		));
+	}
+	Ok(name)
+}
+
+/// Explicit description, or the doc comment
+fn description(attrs: &[Attribute], description: Option<LitStr>, span: Span) -> Result<LitStr> {
+	let description = match description.or_else(|| doc(attrs, span)) {
+		Some(description) => description,
+		None => {
+			return Err(Error::new(
+				span,
+				"missing description, add a doc comment or `description = \"...\"`",
+			))
+		}
+	};
+	let len = description.value().chars().count();
+	if len == 0 || len > MAX_DESCRIPTION {
+		return Err(Error::new(
+			description.span(),
+			"descriptions must be 1 to 100 characters",
+		));
+	}
+	Ok(description)
+}
+
+fn doc(attrs: &[Attribute], span: Span) -> Option<LitStr> {
+	let lines: Vec<_> = attrs
+		.iter()
+		.filter(|a| a.path().is_ident("doc"))
+		.filter_map(|a| match &a.meta.require_name_value().ok()?.value {
+			Expr::Lit(lit) => match &lit.lit {
+				Lit::Str(s) => Some(s.value().trim().to_owned()),
+				_ => None,
+			},
+			_ => None,
+		})
+		.filter(|l| !l.is_empty())
+		.collect();
+	(!lines.is_empty()).then(|| LitStr::new(&lines.join(" "), span))
+}
+
+/// `T` of an `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+	let segment = match ty {
+		Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+		_ => return None,
+	};
+	if segment.ident != "Option" {
+		return None;
+	}
+	match &segment.arguments {
+		PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+			GenericArgument::Type(ty) => Some(ty),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+fn kebab_case(ident: &str) -> String {
+	let mut name = String::new();
+	for (i, c) in ident.chars().enumerate() {
+		if c.is_uppercase() {
+			if i > 0 {
+				name.push('-');
+			}
+			name.extend(c.to_lowercase());
+		} else if c == '_' {
+			name.push('-');
+		} else {
+			name.push(c);
+		}
+	}
+	name
+}
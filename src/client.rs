@@ -1,23 +1,86 @@
+pub use self::attachment::Attachment;
+use self::attachment::Attachments;
+pub use self::audit_log::{
+	AuditLog, AuditLogChange, AuditLogEntry, AuditLogEntryId, AuditLogEvent, AuditLogOptions,
+	AuditLogRole, Change, GetGuildAuditLog, Snowflake,
+};
+pub use self::channel::{
+	ChannelPosition, CreateChannel, EditChannelPermissions, ModifyChannel, ModifyChannelPositions,
+	Overwrite,
+};
+pub use self::command::{
+	CommandPermission, CommandPermissionType, CommandType, GuildCommandPermissions, NewCommand,
+};
+pub use self::embed::EmbedBuilder;
+pub use self::member::{
+	Ban, BulkBan, BulkGuildBan, ModifyGuildMember, RemoveGuildBan, RemoveGuildMember,
+};
+pub use self::modal::{ModalBuilder, TextInputComponent, TextInputStyle};
+use self::pagination::snowflake_timestamp;
+pub use self::pagination::MessageAnchor;
+use self::ratelimit::{RateLimiter, Route};
+pub use self::registry::{CommandChange, CommandRegistry, SyncCommands};
+use self::retry::RateLimited;
+pub use self::retry::RetryPolicy;
+pub use self::role::{CreateGuildRole, ModifyGuildRole, ModifyGuildRolePositions};
+pub use self::thread::{
+	AutoArchiveDuration, CreateForumPost, StartThread, ThreadList, ThreadMember,
+};
+pub use self::user::{CurrentUserGuild, ModifyCurrentUser};
+pub use self::validate::ValidationError;
+pub use self::webhook::{
+	CreateWebhook, EditWebhookMessage, ExecuteWebhook, ModifyWebhook, Webhook, WebhookType,
+};
 use crate::ApiError;
 use discord_types::command::{RequestGuildMembers, UpdateVoiceState};
 use discord_types::request;
 use discord_types::{
-	AllowedMentions, ApplicationCommand, ApplicationCommandOption, ApplicationId, ButtonStyle,
-	ChannelId, Command, Component, ComponentType, Embed, GuildId, InteractionId,
-	InteractionResponseType, Member, Message, MessageId, PartialEmoji, RoleId, User, UserId,
+	AllowedMentions, ApplicationCommandOption, ApplicationId, ButtonStyle, Channel, ChannelId,
+	Command, Component, ComponentType, Embed, GuildId, InteractionId, InteractionResponseType,
+	Member, Message, MessageId, PartialEmoji, RoleId, User, UserId,
 };
 use futures::channel::mpsc;
-use reqwest::multipart::{Form, Part};
-use reqwest::{header, Response};
+use log::debug;
+use reqwest::multipart::Form;
+use reqwest::{header, Method, RequestBuilder, Response};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::OnceCell;
+use tokio::time;
+
+mod attachment;
+mod audit_log;
+mod body;
+mod channel;
+mod command;
+mod embed;
+mod member;
+mod modal;
+mod pagination;
+mod ratelimit;
+mod registry;
+mod retry;
+mod role;
+mod thread;
+mod user;
+mod validate;
+mod webhook;
 
 type CowString = std::borrow::Cow<'static, str>;
 
-const URL_PREFIX: &str = "https://discord.com/api/v8/";
+const BASE_URL: &str = "https://discord.com/api";
+const API_VERSION: u8 = 10;
+/// Milliseconds between the Unix epoch and the Discord epoch
+const DISCORD_EPOCH: u64 = 1_420_070_400_000;
+const MESSAGE_SUPPRESS_EMBEDS: u64 = 1 << 2;
+const MESSAGE_SUPPRESS_NOTIFICATIONS: u64 = 1 << 12;
+const INTERACTION_AUTOCOMPLETE_RESULT: u8 = 8;
+/// Slightly less than the 14 days allowed by Discord, to account for clock skew
+const BULK_DELETE_MAX_AGE: Duration = Duration::from_secs(14 * 24 * 3600 - 60);
 
 #[derive(Debug)]
 pub enum Error {
@@ -33,6 +96,7 @@ pub enum Error {
 	Response(u16),
 	Api(ApiError),
 	Decode(serde_json::Error),
+	Validation(ValidationError),
 	Other(reqwest::Error),
 }
 
@@ -40,9 +104,38 @@ impl Error {
 	pub fn is_not_found(&self) -> bool {
 		match self {
 			Error::NotFound => true,
+			Error::Api(e) => e.status() == 404,
 			_ => false,
 		}
 	}
+
+	pub fn api_error(&self) -> Option<&ApiError> {
+		match self {
+			Error::Api(e) => Some(e),
+			_ => None,
+		}
+	}
+
+	/// Discord's JSON error code, e.g. `ApiError::MISSING_PERMISSIONS`
+	pub fn api_code(&self) -> Option<u32> {
+		self.api_error().map(|e| e.code())
+	}
+
+	/// HTTP status code of the response that caused this error
+	pub fn status(&self) -> Option<u16> {
+		match self {
+			Error::BadRequest => Some(400),
+			Error::InvalidToken => Some(401),
+			Error::NotPermitted => Some(403),
+			Error::NotFound => Some(404),
+			Error::RateLimited => Some(429),
+			Error::GatewayUnavailable => Some(502),
+			Error::Response(c) => Some(*c),
+			Error::Api(e) => Some(e.status()),
+			Error::Other(e) => e.status().map(|s| s.as_u16()),
+			_ => None,
+		}
+	}
 }
 
 impl fmt::Display for Error {
@@ -60,6 +153,7 @@ impl fmt::Display for Error {
 			Error::Response(c) => write!(f, "Response code {}", *c),
 			Error::Api(e) => fmt::Display::fmt(e, f),
 			Error::Decode(e) => fmt::Display::fmt(e, f),
+			Error::Validation(e) => fmt::Display::fmt(e, f),
 			Error::Other(e) => fmt::Display::fmt(e, f),
 		}
 	}
@@ -105,6 +199,12 @@ impl From<ApiError> for Error {
 	}
 }
 
+impl From<ValidationError> for Error {
+	fn from(e: ValidationError) -> Self {
+		Self::Validation(e)
+	}
+}
+
 impl From<serde_json::Error> for Error {
 	fn from(e: serde_json::Error) -> Self {
 		Self::Decode(e)
@@ -129,38 +229,163 @@ impl<T> OptionalResult for Result<T, Error> {
 	}
 }
 
-#[derive(Clone, Debug)]
-pub struct Client {
-	client: reqwest::Client,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenType {
+	Bot,
+	Bearer,
+}
+
+impl fmt::Display for TokenType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TokenType::Bot => write!(f, "Bot"),
+			TokenType::Bearer => write!(f, "Bearer"),
+		}
+	}
+}
+
+pub struct ClientBuilder {
+	token: Option<String>,
+	token_type: TokenType,
+	base_url: String,
+	api_version: u8,
+	timeout: Duration,
+	user_agent_suffix: Option<String>,
+	retry_policy: RetryPolicy,
 	command_send: Option<mpsc::Sender<Command>>,
 }
 
-impl Client {
-	pub fn new(token: &str, command_send: Option<mpsc::Sender<Command>>) -> Result<Self, Error> {
+impl ClientBuilder {
+	pub fn new<T: Into<String>>(token: T) -> Self {
+		Self {
+			token: Some(token.into()),
+			token_type: TokenType::Bot,
+			base_url: BASE_URL.into(),
+			api_version: API_VERSION,
+			timeout: Duration::from_secs(10),
+			user_agent_suffix: None,
+			retry_policy: RetryPolicy::default(),
+			command_send: None,
+		}
+	}
+
+	/// Client without a token, only usable for endpoints that are authenticated
+	/// by a webhook token, e.g. `Client::execute_webhook_url`
+	pub fn unauthenticated() -> Self {
+		let mut builder = Self::new("");
+		builder.token = None;
+		builder
+	}
+
+	pub fn token_type(mut self, token_type: TokenType) -> Self {
+		self.token_type = token_type;
+		self
+	}
+
+	/// Authenticate with an OAuth2 bearer token instead of a bot token
+	pub fn bearer(self) -> Self {
+		self.token_type(TokenType::Bearer)
+	}
+
+	/// Base URL of the API, without the version. Defaults to `https://discord.com/api`
+	pub fn base_url<T: Into<String>>(mut self, base_url: T) -> Self {
+		self.base_url = base_url.into();
+		self
+	}
+
+	pub fn api_version(mut self, api_version: u8) -> Self {
+		self.api_version = api_version;
+		self
+	}
+
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Appended to the default user agent
+	pub fn user_agent_suffix<T: Into<String>>(mut self, suffix: T) -> Self {
+		self.user_agent_suffix = Some(suffix.into());
+		self
+	}
+
+	pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+		self.retry_policy = retry_policy;
+		self
+	}
+
+	/// Channel to the gateway, required for gateway commands like `Client::request_guild_members`
+	pub fn command_send(mut self, command_send: mpsc::Sender<Command>) -> Self {
+		self.command_send = Some(command_send);
+		self
+	}
+
+	pub fn build(self) -> Result<Client, Error> {
+		let mut user_agent = format!(
+			"discord-async-rs (github.com/kebabtent/discord-async-rs, v{})",
+			env!("CARGO_PKG_VERSION")
+		);
+		if let Some(suffix) = &self.user_agent_suffix {
+			user_agent.push(' ');
+			user_agent.push_str(suffix);
+		}
+
 		let mut headers = header::HeaderMap::new();
 		headers.insert(
 			header::USER_AGENT,
-			header::HeaderValue::from_str(&format!(
-				"discord-async-rs (github.com/kebabtent/discord-async-rs, v{})",
-				env!("CARGO_PKG_VERSION")
-			))?,
-		);
-		headers.insert(
-			header::AUTHORIZATION,
-			header::HeaderValue::from_str(&format!("Bot {}", token))?,
+			header::HeaderValue::from_str(&user_agent)?,
 		);
+		if let Some(token) = &self.token {
+			headers.insert(
+				header::AUTHORIZATION,
+				header::HeaderValue::from_str(&format!("{} {}", self.token_type, token))?,
+			);
+		}
 
 		let client = reqwest::ClientBuilder::new()
 			.default_headers(headers)
-			.timeout(Duration::from_secs(10))
+			.timeout(self.timeout)
 			.use_rustls_tls()
 			.build()?;
 
-		Ok(Self {
+		let base_url = format!(
+			"{}/v{}/",
+			self.base_url.trim_end_matches('/'),
+			self.api_version
+		);
+
+		Ok(Client {
 			client,
-			command_send,
+			base_url: base_url.into(),
+			ratelimit: Arc::new(RateLimiter::new()),
+			retry_policy: self.retry_policy,
+			command_send: self.command_send,
+			dm_channels: Arc::new(Mutex::new(HashMap::new())),
 		})
 	}
+}
+
+#[derive(Clone, Debug)]
+pub struct Client {
+	client: reqwest::Client,
+	base_url: Arc<str>,
+	ratelimit: Arc<RateLimiter>,
+	retry_policy: RetryPolicy,
+	command_send: Option<mpsc::Sender<Command>>,
+	/// Filled once per user, concurrent `create_dm` calls wait for the same request
+	dm_channels: Arc<Mutex<HashMap<UserId, Arc<OnceCell<Channel>>>>>,
+}
+
+impl Client {
+	pub fn new(token: &str, command_send: Option<mpsc::Sender<Command>>) -> Result<Self, Error> {
+		let mut builder = ClientBuilder::new(token);
+		builder.command_send = command_send;
+		builder.build()
+	}
+
+	pub fn builder<T: Into<String>>(token: T) -> ClientBuilder {
+		ClientBuilder::new(token)
+	}
 
 	fn command_send(&mut self) -> Result<&mut mpsc::Sender<Command>, ()> {
 		self.command_send.as_mut().ok_or(())
@@ -195,105 +420,154 @@ impl Client {
 		self.command_send()?.try_send(upd.into()).map_err(|_| ())
 	}
 
+	pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+		self.retry_policy = retry_policy;
+	}
+
+	async fn send<F>(&self, method: Method, url: &str, build: F) -> Result<Response, Error>
+	where
+		F: FnOnce(RequestBuilder) -> RequestBuilder,
+	{
+		let route = Route::new(&method, url);
+		let mut next = Some(build(
+			self.client
+				.request(method.clone(), format!("{}{}", self.base_url, url)),
+		));
+		let mut attempt = 0;
+
+		loop {
+			// Multipart bodies can't be cloned, these requests are never retried
+			let req = next.take().unwrap();
+			next = req.try_clone();
+			attempt += 1;
+
+			let ticket = self.ratelimit.acquire(&route).await;
+			// The URL can hold webhook and interaction tokens, keep it out of errors
+			let res = req.send().await.map_err(reqwest::Error::without_url)?;
+			ticket.complete(&res);
+
+			let status = res.status();
+			if !status.is_client_error() && !status.is_server_error() {
+				return Ok(res);
+			}
+			let err = res.error_for_status_ref().unwrap_err().without_url();
+			let header_retry_after = ratelimit::retry_after(res.headers());
+			let full = match res.bytes().await {
+				Ok(f) => f,
+				Err(_) => return Err(err.into()),
+			};
+			let retry_after = serde_json::from_slice::<RateLimited>(&full)
+				.ok()
+				.map(|r| r.retry_after())
+				.or(header_retry_after);
+
+			let delay = self
+				.retry_policy
+				.delay(&method, status, retry_after, attempt)
+				.filter(|_| next.is_some());
+			match delay {
+				Some(delay) => {
+					debug!(
+						"Request to '{}' failed with {}, retrying in {:?}",
+						route, status, delay
+					);
+					time::sleep(delay).await;
+				}
+				None => return Err(response_error(err, &route, &full)),
+			}
+		}
+	}
+
 	async fn get<D>(&self, url: &str) -> Result<D, Error>
 	where
 		D: DeserializeOwned,
 	{
-		decode(
-			self.client
-				.get(&format!("{}{}", URL_PREFIX, url))
-				.send()
-				.await?,
-		)
-		.await
+		decode(self.send(Method::GET, url, |r| r).await?).await
 	}
 
-	async fn post<S, D>(&self, url: &str, body: S) -> Result<D, Error>
+	async fn post<S, D>(&self, url: &str, body: S, reason: Option<&str>) -> Result<D, Error>
 	where
 		S: Serialize,
 		D: DeserializeOwned,
 	{
-		decode(
-			self.client
-				.post(&format!("{}{}", URL_PREFIX, url))
-				.header(header::CONTENT_TYPE, "application/json")
-				.json(&body)
-				.send()
-				.await?,
-		)
-		.await
+		let res = self
+			.send(Method::POST, url, |r| audit(json(r, &body), reason))
+			.await?;
+		decode(res).await
 	}
 
-	async fn post_discard<S>(&self, url: &str, body: S) -> Result<(), Error>
+	async fn post_discard<S>(&self, url: &str, body: S, reason: Option<&str>) -> Result<(), Error>
 	where
 		S: Serialize,
 	{
-		check_response_code(
-			self.client
-				.post(&format!("{}{}", URL_PREFIX, url))
-				.header(header::CONTENT_TYPE, "application/json")
-				.json(&body)
-				.send()
-				.await?,
-		)
-		.await?;
+		self.send(Method::POST, url, |r| audit(json(r, &body), reason))
+			.await?;
 		Ok(())
 	}
 
-	async fn post_multipart<D>(&self, url: &str, multipart: Form) -> Result<D, Error>
+	async fn post_multipart<D>(
+		&self,
+		url: &str,
+		multipart: Form,
+		reason: Option<&str>,
+	) -> Result<D, Error>
 	where
 		D: DeserializeOwned,
 	{
-		decode(
-			self.client
-				.post(&format!("{}{}", URL_PREFIX, url))
-				.multipart(multipart)
-				.send()
-				.await?,
-		)
-		.await
+		let res = self
+			.send(Method::POST, url, |r| audit(r.multipart(multipart), reason))
+			.await?;
+		decode(res).await
 	}
 
-	async fn put<S>(&self, url: &str, body: S) -> Result<(), Error>
+	async fn patch_multipart<D>(
+		&self,
+		url: &str,
+		multipart: Form,
+		reason: Option<&str>,
+	) -> Result<D, Error>
+	where
+		D: DeserializeOwned,
+	{
+		let res = self
+			.send(Method::PATCH, url, |r| {
+				audit(r.multipart(multipart), reason)
+			})
+			.await?;
+		decode(res).await
+	}
+
+	async fn put<S>(&self, url: &str, body: S, reason: Option<&str>) -> Result<(), Error>
 	where
 		S: Serialize,
 	{
-		check_response_code(
-			self.client
-				.put(&format!("{}{}", URL_PREFIX, url))
-				.header(header::CONTENT_TYPE, "application/json")
-				.json(&body)
-				.send()
-				.await?,
-		)
-		.await?;
+		self.send(Method::PUT, url, |r| audit(json(r, &body), reason))
+			.await?;
 		Ok(())
 	}
 
-	async fn patch<S, D>(&self, url: &str, body: S) -> Result<D, Error>
+	async fn patch<S, D>(&self, url: &str, body: S, reason: Option<&str>) -> Result<D, Error>
 	where
 		S: Serialize,
 		D: DeserializeOwned,
 	{
-		decode(
-			self.client
-				.patch(&format!("{}{}", URL_PREFIX, url))
-				.header(header::CONTENT_TYPE, "application/json")
-				.json(&body)
-				.send()
-				.await?,
-		)
-		.await
+		let res = self
+			.send(Method::PATCH, url, |r| audit(json(r, &body), reason))
+			.await?;
+		decode(res).await
 	}
 
-	async fn delete(&self, url: &str) -> Result<(), Error> {
-		check_response_code(
-			self.client
-				.delete(&format!("{}{}", URL_PREFIX, url))
-				.send()
-				.await?,
-		)
-		.await?;
+	async fn patch_discard<S>(&self, url: &str, body: S, reason: Option<&str>) -> Result<(), Error>
+	where
+		S: Serialize,
+	{
+		self.send(Method::PATCH, url, |r| audit(json(r, &body), reason))
+			.await?;
+		Ok(())
+	}
+
+	async fn delete(&self, url: &str, reason: Option<&str>) -> Result<(), Error> {
+		self.send(Method::DELETE, url, |r| audit(r, reason)).await?;
 		Ok(())
 	}
 
@@ -306,6 +580,21 @@ impl Client {
 			.await
 	}
 
+	pub async fn get_channel_messages(
+		&self,
+		channel_id: ChannelId,
+		anchor: Option<MessageAnchor>,
+		limit: Option<u8>,
+	) -> Result<Vec<Message>, Error> {
+		let limit = limit.unwrap_or(100).clamp(1, 100);
+		let params = anchor.map(|a| a.query()).unwrap_or_default();
+		self.get(&format!(
+			"channels/{}/messages?limit={}{}",
+			channel_id, limit, params
+		))
+		.await
+	}
+
 	pub async fn get_reactions(
 		&self,
 		channel_id: ChannelId,
@@ -314,12 +603,7 @@ impl Client {
 		after: Option<UserId>,
 		limit: Option<u8>,
 	) -> Result<Vec<User>, Error> {
-		let name = emoji.name.as_deref().ok_or(Error::BadRequest)?;
-		let name = match emoji.id {
-			Some(id) => format!("{}:{}", name, id),
-			None => name.to_string(),
-		};
-		let name = urlencoding::encode(&name);
+		let name = encode_emoji(emoji)?;
 		let limit = limit.unwrap_or(100).min(100);
 		let params = if let Some(user_id) = after {
 			format!("&after={}", user_id)
@@ -334,10 +618,118 @@ impl Client {
 		.await
 	}
 
+	pub async fn create_reaction(
+		&self,
+		channel_id: ChannelId,
+		message_id: MessageId,
+		emoji: &PartialEmoji,
+	) -> Result<(), Error> {
+		self.put(
+			&format!(
+				"channels/{}/messages/{}/reactions/{}/@me",
+				channel_id,
+				message_id,
+				encode_emoji(emoji)?
+			),
+			(),
+			None,
+		)
+		.await
+	}
+
+	pub async fn delete_own_reaction(
+		&self,
+		channel_id: ChannelId,
+		message_id: MessageId,
+		emoji: &PartialEmoji,
+	) -> Result<(), Error> {
+		self.delete(
+			&format!(
+				"channels/{}/messages/{}/reactions/{}/@me",
+				channel_id,
+				message_id,
+				encode_emoji(emoji)?
+			),
+			None,
+		)
+		.await
+	}
+
+	pub async fn delete_user_reaction(
+		&self,
+		channel_id: ChannelId,
+		message_id: MessageId,
+		emoji: &PartialEmoji,
+		user_id: UserId,
+	) -> Result<(), Error> {
+		self.delete(
+			&format!(
+				"channels/{}/messages/{}/reactions/{}/{}",
+				channel_id,
+				message_id,
+				encode_emoji(emoji)?,
+				user_id
+			),
+			None,
+		)
+		.await
+	}
+
+	pub async fn delete_all_reactions(
+		&self,
+		channel_id: ChannelId,
+		message_id: MessageId,
+	) -> Result<(), Error> {
+		self.delete(
+			&format!("channels/{}/messages/{}/reactions", channel_id, message_id),
+			None,
+		)
+		.await
+	}
+
+	pub async fn delete_all_reactions_for_emoji(
+		&self,
+		channel_id: ChannelId,
+		message_id: MessageId,
+		emoji: &PartialEmoji,
+	) -> Result<(), Error> {
+		self.delete(
+			&format!(
+				"channels/{}/messages/{}/reactions/{}",
+				channel_id,
+				message_id,
+				encode_emoji(emoji)?
+			),
+			None,
+		)
+		.await
+	}
+
+	pub async fn get_pinned_messages(&self, channel_id: ChannelId) -> Result<Vec<Message>, Error> {
+		self.get(&format!("channels/{}/pins", channel_id)).await
+	}
+
+	pub fn pin_message(&self, channel_id: ChannelId, message_id: MessageId) -> AuditRequest<'_> {
+		AuditRequest::new(
+			self,
+			Method::PUT,
+			format!("channels/{}/pins/{}", channel_id, message_id),
+		)
+	}
+
+	pub fn unpin_message(&self, channel_id: ChannelId, message_id: MessageId) -> AuditRequest<'_> {
+		AuditRequest::new(
+			self,
+			Method::DELETE,
+			format!("channels/{}/pins/{}", channel_id, message_id),
+		)
+	}
+
 	pub fn create_message(&self, channel_id: ChannelId) -> CreateMessageBuilder<'_> {
 		CreateMessageBuilder {
-			client: &self,
-			channel_id: channel_id.into(),
+			client: self,
+			channel_id,
+			cm: Default::default(),
 		}
 	}
 
@@ -345,13 +737,31 @@ impl Client {
 		EditMessage::new(self, channel_id, message_id)
 	}
 
-	pub async fn delete_message<T: Into<(ChannelId, MessageId)>>(
-		&self,
-		ids: T,
-	) -> Result<(), Error> {
+	/// Delete multiple messages at once.
+	/// Discord only allows bulk deleting messages younger than 14 days, so older messages
+	/// are deleted one by one. Requests are split into chunks of 100 messages,
+	/// duplicate ids are ignored
+	pub fn bulk_delete_messages<I>(&self, channel_id: ChannelId, ids: I) -> BulkDeleteMessages<'_>
+	where
+		I: IntoIterator<Item = MessageId>,
+	{
+		// Discord rejects a bulk delete with duplicate ids
+		let mut seen = HashSet::new();
+		BulkDeleteMessages {
+			client: self,
+			channel_id,
+			ids: ids.into_iter().filter(|&id| seen.insert(id)).collect(),
+			reason: None,
+		}
+	}
+
+	pub fn delete_message<T: Into<(ChannelId, MessageId)>>(&self, ids: T) -> AuditRequest<'_> {
 		let (channel_id, message_id) = ids.into();
-		self.delete(&format!("channels/{}/messages/{}", channel_id, message_id))
-			.await
+		AuditRequest::new(
+			self,
+			Method::DELETE,
+			format!("channels/{}/messages/{}", channel_id, message_id),
+		)
 	}
 
 	pub fn create_guild_ban<T: Into<GuildId>, U: Into<UserId>>(
@@ -363,25 +773,12 @@ impl Client {
 			client: &self,
 			guild_id: guild_id.into(),
 			user_id: user_id.into(),
-			cgb: request::CreateGuildBan {
-				delete_message_days: None,
-				reason: None,
-			},
+			reason: None,
+			cgb: Default::default(),
 		}
 	}
 
-	pub(crate) async fn commands(
-		&self,
-		application_id: ApplicationId,
-		guild_id: GuildId,
-	) -> Result<Vec<ApplicationCommand>, Error> {
-		self.get(&format!(
-			"applications/{}/guilds/{}/commands",
-			application_id, guild_id
-		))
-		.await
-	}
-
+	/// Guild command with only a name, description and options, see `Client::create_guild_command`
 	pub async fn create_command(
 		&self,
 		application_id: ApplicationId,
@@ -402,6 +799,7 @@ impl Client {
 				application_id, guild_id
 			),
 			body,
+			None,
 		)
 		.await
 	}
@@ -415,6 +813,37 @@ impl Client {
 		InteractionResponse::new(self, interaction_id, token, component)
 	}
 
+	/// Suggestions for the focused option of an autocomplete interaction, see
+	/// `CanRespond::autocomplete`
+	pub async fn autocomplete_response(
+		&self,
+		interaction_id: InteractionId,
+		token: &str,
+		choices: Vec<(CowString, serde_json::Value)>,
+	) -> Result<(), Error> {
+		validate::count("autocomplete choices", choices.len(), validate::MAX_CHOICES)?;
+		for (name, value) in &choices {
+			validate::len("choice name", name, 100)?;
+			if let Some(value) = value.as_str() {
+				validate::len("choice value", value, 100)?;
+			}
+		}
+
+		let choices = choices
+			.into_iter()
+			.map(|(name, value)| body::Choice { name, value })
+			.collect();
+		self.post_discard(
+			&format!("interactions/{}/{}/callback", interaction_id, token),
+			body::AutocompleteResponse {
+				response_type: INTERACTION_AUTOCOMPLETE_RESULT,
+				data: body::AutocompleteData { choices },
+			},
+			None,
+		)
+		.await
+	}
+
 	pub async fn get_guild_members(
 		&self,
 		guild_id: GuildId,
@@ -442,59 +871,233 @@ impl Client {
 			.await
 	}
 
-	pub async fn add_guild_member_role(
+	pub fn add_guild_member_role(
 		&self,
 		guild_id: GuildId,
 		user_id: UserId,
 		role_id: RoleId,
-	) -> Result<(), Error> {
-		self.put(
-			&format!("guilds/{}/members/{}/roles/{}", guild_id, user_id, role_id),
-			(),
+	) -> AuditRequest<'_> {
+		AuditRequest::new(
+			self,
+			Method::PUT,
+			format!("guilds/{}/members/{}/roles/{}", guild_id, user_id, role_id),
 		)
-		.await
 	}
 
-	pub async fn remove_guild_member_role(
+	pub fn remove_guild_member_role(
 		&self,
 		guild_id: GuildId,
 		user_id: UserId,
 		role_id: RoleId,
-	) -> Result<(), Error> {
-		self.delete(&format!(
-			"guilds/{}/members/{}/roles/{}",
-			guild_id, user_id, role_id
-		))
-		.await
+	) -> AuditRequest<'_> {
+		AuditRequest::new(
+			self,
+			Method::DELETE,
+			format!("guilds/{}/members/{}/roles/{}", guild_id, user_id, role_id),
+		)
 	}
 }
 
+/// URL-encoded emoji as used in the reaction endpoints, `name:id` for custom emoji
+fn encode_emoji(emoji: &PartialEmoji) -> Result<String, Error> {
+	let name = emoji.name.as_deref().ok_or(Error::BadRequest)?;
+	let name = match emoji.id {
+		Some(id) => format!("{}:{}", name, id),
+		None => name.to_string(),
+	};
+	Ok(urlencoding::encode(&name).into_owned())
+}
+
+/// Attach the reason for a moderation action, shown in the audit log
+fn audit(req: RequestBuilder, reason: Option<&str>) -> RequestBuilder {
+	match reason {
+		Some(reason) => req.header("X-Audit-Log-Reason", urlencoding::encode(reason).as_ref()),
+		None => req,
+	}
+}
+
+fn json<S: Serialize>(req: RequestBuilder, body: &S) -> RequestBuilder {
+	req.header(header::CONTENT_TYPE, "application/json")
+		.json(body)
+}
+
 async fn decode<D: DeserializeOwned>(res: Response) -> Result<D, Error> {
-	let res = check_response_code(res).await?;
-	let full = res.bytes().await?;
+	let full = res.bytes().await.map_err(reqwest::Error::without_url)?;
 	serde_json::from_slice(&full).map_err(|e| Error::Decode(e))
 }
 
-async fn check_response_code(res: Response) -> Result<Response, Error> {
-	match res.error_for_status_ref() {
-		Ok(_) => Ok(res),
-		Err(err) => {
-			let full = match res.bytes().await {
-				Ok(f) => f,
-				Err(_) => return Err(err.into()),
-			};
-			let api_err = serde_json::from_slice::<ApiError>(&full);
-			match api_err {
-				Ok(err) => Err(err.into()),
-				Err(_) => Err(err.into()),
+/// The masked route is used, since the path can hold webhook and interaction tokens
+fn response_error(err: reqwest::Error, route: &Route, body: &[u8]) -> Error {
+	match serde_json::from_slice::<ApiError>(body) {
+		Ok(mut api_err) => {
+			api_err.status = err.status().map(|s| s.as_u16()).unwrap_or(0);
+			api_err.route = route.to_string();
+			api_err.into()
+		}
+		Err(_) => err.into(),
+	}
+}
+
+/// Request without a body, that only accepts the reason shown in the audit log
+pub struct AuditRequest<'a> {
+	client: &'a Client,
+	method: Method,
+	url: String,
+	reason: Option<&'a str>,
+}
+
+impl<'a> AuditRequest<'a> {
+	fn new(client: &'a Client, method: Method, url: String) -> Self {
+		Self {
+			client,
+			method,
+			url,
+			reason: None,
+		}
+	}
+
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<(), Error> {
+		let Self {
+			client,
+			method,
+			url,
+			reason,
+		} = self;
+		let has_body = method == Method::PUT;
+		client
+			.send(method, &url, |r| {
+				let r = if has_body { json(r, &()) } else { r };
+				audit(r, reason)
+			})
+			.await?;
+		Ok(())
+	}
+}
+
+pub struct BulkDeleteMessages<'a> {
+	client: &'a Client,
+	channel_id: ChannelId,
+	ids: Vec<MessageId>,
+	reason: Option<&'a str>,
+}
+
+impl<'a> BulkDeleteMessages<'a> {
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<(), Error> {
+		let min_age = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_millis() as u64)
+			.unwrap_or(0)
+			.saturating_sub(BULK_DELETE_MAX_AGE.as_millis() as u64);
+		let (recent, old): (Vec<_>, Vec<_>) = self
+			.ids
+			.into_iter()
+			.partition(|&id| snowflake_timestamp(id) > min_age);
+
+		let client = self.client;
+		let delete = |id| {
+			let req = client.delete_message((self.channel_id, id));
+			match self.reason {
+				Some(reason) => req.reason(reason),
+				None => req,
+			}
+		};
+
+		let url = format!("channels/{}/messages/bulk-delete", self.channel_id);
+		for chunk in recent.chunks(100) {
+			match chunk {
+				&[id] => delete(id).send().await?,
+				messages => {
+					client
+						.post_discard(&url, body::BulkDeleteMessages { messages }, self.reason)
+						.await?
+				}
 			}
 		}
+
+		for id in old {
+			delete(id).send().await?;
+		}
+		Ok(())
 	}
 }
 
+/// Message options that don't count as content, shared by `CreateMessageBuilder`
+/// and `CreateMessage`
+macro_rules! message_options {
+	($t:ident) => {
+		impl<'a> $t<'a> {
+			/// Reply to a message in the same channel
+			pub fn reply(mut self, message_id: MessageId) -> Self {
+				self.cm.message_reference = Some(body::MessageReference {
+					message_id,
+					channel_id: None,
+					guild_id: None,
+					fail_if_not_exists: self.cm.fail_if_not_exists,
+				});
+				self
+			}
+
+			/// Whether sending a reply fails if the referenced message was deleted,
+			/// instead of sending it as a normal message. Defaults to true
+			pub fn fail_if_not_exists(mut self, fail: bool) -> Self {
+				self.cm.fail_if_not_exists = Some(fail);
+				if let Some(reference) = &mut self.cm.message_reference {
+					reference.fail_if_not_exists = Some(fail);
+				}
+				self
+			}
+
+			pub fn allowed_mentions(mut self, m: AllowedMentions) -> Self {
+				self.cm.allowed_mentions = Some(m);
+				self
+			}
+
+			pub fn tts(mut self) -> Self {
+				self.cm.tts = Some(true);
+				self
+			}
+
+			/// Used to verify the message was sent, returned in the `MessageCreate` event
+			pub fn nonce<T: Into<CowString>>(mut self, nonce: T) -> Self {
+				self.cm.nonce = Some(nonce.into());
+				self
+			}
+
+			/// If a message with the same nonce was sent by the bot in the last few minutes,
+			/// return that message instead of sending a new one
+			pub fn enforce_nonce(mut self) -> Self {
+				self.cm.enforce_nonce = Some(true);
+				self
+			}
+
+			pub fn suppress_embeds(mut self) -> Self {
+				*self.cm.flags.get_or_insert(0) |= MESSAGE_SUPPRESS_EMBEDS;
+				self
+			}
+
+			/// Don't trigger push and desktop notifications
+			pub fn silent(mut self) -> Self {
+				*self.cm.flags.get_or_insert(0) |= MESSAGE_SUPPRESS_NOTIFICATIONS;
+				self
+			}
+		}
+	};
+}
+
 pub struct CreateMessageBuilder<'a> {
 	client: &'a Client,
 	channel_id: ChannelId,
+	cm: body::CreateMessage,
 }
 
 impl<'a> CreateMessageBuilder<'a> {
@@ -502,12 +1105,8 @@ impl<'a> CreateMessageBuilder<'a> {
 		CreateMessage {
 			client: self.client,
 			channel_id: self.channel_id,
-			attachment: None,
-			cm: request::CreateMessage {
-				content: None,
-				embeds: Vec::new(),
-				components: Vec::new(),
-			},
+			attachments: Default::default(),
+			cm: self.cm,
 		}
 	}
 
@@ -528,13 +1127,23 @@ impl<'a> CreateMessageBuilder<'a> {
 		cm.cm.embeds = embeds.into_iter().collect();
 		cm
 	}
+
+	pub fn attachment<T: Into<Attachment>>(self, attachment: T) -> CreateMessage<'a> {
+		self.into().attachment(attachment)
+	}
+
+	pub fn sticker<T: fmt::Display>(self, sticker_id: T) -> CreateMessage<'a> {
+		self.into().sticker(sticker_id)
+	}
 }
 
+message_options!(CreateMessageBuilder);
+
 pub struct CreateMessage<'a> {
 	client: &'a Client,
 	channel_id: ChannelId,
-	attachment: Option<request::Attachment>,
-	cm: request::CreateMessage,
+	attachments: Attachments,
+	cm: body::CreateMessage,
 }
 
 impl<'a> CreateMessage<'a> {
@@ -548,8 +1157,26 @@ impl<'a> CreateMessage<'a> {
 		self
 	}
 
-	pub fn attachment(mut self, attachment: request::Attachment) -> Self {
-		self.attachment = Some(attachment);
+	/// Upload a file with the message, can be called multiple times
+	pub fn attachment<T: Into<Attachment>>(mut self, attachment: T) -> Self {
+		self.attachments.push(attachment.into());
+		self
+	}
+
+	pub fn attachments<I>(mut self, attachments: I) -> Self
+	where
+		I: IntoIterator,
+		I::Item: Into<Attachment>,
+	{
+		for attachment in attachments {
+			self.attachments.push(attachment.into());
+		}
+		self
+	}
+
+	/// Up to 3 stickers
+	pub fn sticker<T: fmt::Display>(mut self, sticker_id: T) -> Self {
+		self.cm.sticker_ids.push(sticker_id.to_string());
 		self
 	}
 
@@ -563,20 +1190,92 @@ impl<'a> CreateMessage<'a> {
 		self
 	}
 
+	fn validate(&self) -> Result<(), ValidationError> {
+		validate::message(
+			self.cm.content.as_deref(),
+			&self.cm.embeds,
+			&self.cm.components,
+		)?;
+		validate::count(
+			"stickers",
+			self.cm.sticker_ids.len(),
+			validate::MAX_STICKERS,
+		)?;
+		validate::count(
+			"attachments",
+			self.attachments.len(),
+			validate::MAX_ATTACHMENTS,
+		)
+	}
+
 	pub async fn send(self) -> Result<Message, Error> {
+		self.validate()?;
 		let url = format!("channels/{}/messages", self.channel_id);
 
-		if let Some(attachment) = self.attachment {
-			let multipart = Form::new()
-				.part(
-					"file",
-					Part::bytes(attachment.data).file_name(attachment.name),
-				)
-				.text("payload_json", serde_json::to_string(&self.cm)?);
-			self.client.post_multipart(&url, multipart).await
+		if self.attachments.has_files() {
+			let multipart = self.attachments.multipart(self.cm)?;
+			self.client.post_multipart(&url, multipart, None).await
 		} else {
-			self.client.post(&url, self.cm).await
+			self.client.post(&url, self.cm, None).await
+		}
+	}
+
+	/// Like `send`, but content that is too long is split over multiple messages,
+	/// on line breaks and code block boundaries where possible. Embeds, components,
+	/// attachments and stickers are sent with the last message, the reply with the first.
+	/// The last message is validated before anything is sent
+	pub async fn send_split(mut self) -> Result<Vec<Message>, Error> {
+		let mut chunks = match &self.cm.content {
+			Some(content) if content.chars().count() > validate::MAX_CONTENT => {
+				validate::split(content, validate::MAX_CONTENT)
+			}
+			_ => return Ok(vec![self.send().await?]),
+		};
+		let last = chunks.pop().unwrap_or_default();
+		self.cm.content = Some(last.into());
+		self.validate()?;
+
+		let mut messages = Vec::with_capacity(chunks.len() + 1);
+		for chunk in chunks {
+			let cm = CreateMessage {
+				client: self.client,
+				channel_id: self.channel_id,
+				attachments: Default::default(),
+				cm: body::CreateMessage {
+					content: Some(chunk.into()),
+					tts: self.cm.tts,
+					message_reference: self.cm.message_reference.clone(),
+					allowed_mentions: self.cm.allowed_mentions.clone(),
+					flags: self.cm.flags,
+					..Default::default()
+				},
+			};
+			messages.push(cm.send().await?);
+			// Only the first message is a reply
+			self.cm.message_reference = None;
 		}
+
+		messages.push(self.send().await?);
+		Ok(messages)
+	}
+}
+
+message_options!(CreateMessage);
+
+pub trait CanReply {
+	fn reply<'a>(&self, client: &'a Client) -> CreateMessageBuilder<'a>;
+}
+
+impl CanReply for Message {
+	fn reply<'a>(&self, client: &'a Client) -> CreateMessageBuilder<'a> {
+		let mut builder = client.create_message(self.channel_id);
+		builder.cm.message_reference = Some(body::MessageReference {
+			message_id: self.id,
+			channel_id: Some(self.channel_id),
+			guild_id: self.guild_id,
+			fail_if_not_exists: None,
+		});
+		builder
 	}
 }
 
@@ -584,7 +1283,8 @@ pub struct EditMessage<'a> {
 	client: &'a Client,
 	channel_id: ChannelId,
 	message_id: MessageId,
-	em: request::EditMessage,
+	attachments: Attachments,
+	em: body::EditMessage,
 }
 
 impl<'a> EditMessage<'a> {
@@ -593,11 +1293,8 @@ impl<'a> EditMessage<'a> {
 			client,
 			channel_id,
 			message_id,
-			em: request::EditMessage {
-				content: None,
-				embeds: None,
-				components: None,
-			},
+			attachments: Default::default(),
+			em: Default::default(),
 		}
 	}
 
@@ -624,9 +1321,66 @@ impl<'a> EditMessage<'a> {
 		self
 	}
 
-	pub async fn send(self) -> Result<Message, Error> {
+	pub fn allowed_mentions(mut self, m: AllowedMentions) -> Self {
+		self.em.allowed_mentions = Some(m);
+		self
+	}
+
+	/// Hide or show the embeds of links in the message
+	pub fn suppress_embeds(mut self, suppress: bool) -> Self {
+		let flags = self.em.flags.get_or_insert(0);
+		if suppress {
+			*flags |= MESSAGE_SUPPRESS_EMBEDS;
+		} else {
+			*flags &= !MESSAGE_SUPPRESS_EMBEDS;
+		}
+		self
+	}
+
+	/// Upload a file, existing attachments are kept unless `keep_attachment` or
+	/// `remove_attachments` is used. Keeping them all costs an extra request to fetch their ids
+	pub fn attachment<T: Into<Attachment>>(mut self, attachment: T) -> Self {
+		self.attachments.push(attachment.into());
+		self
+	}
+
+	/// Keep an existing attachment, all others are removed
+	pub fn keep_attachment<T: fmt::Display>(mut self, attachment_id: T) -> Self {
+		self.attachments.keep(attachment_id.to_string());
+		self
+	}
+
+	/// Remove all existing attachments, except those passed to `keep_attachment`
+	pub fn remove_attachments(mut self) -> Self {
+		self.attachments.remove_existing();
+		self
+	}
+
+	pub async fn send(mut self) -> Result<Message, Error> {
+		validate::message(
+			self.em.content.as_deref(),
+			self.em.embeds.as_deref().unwrap_or(&[]),
+			self.em.components.as_deref().unwrap_or(&[]),
+		)?;
+		validate::count(
+			"attachments",
+			self.attachments.len(),
+			validate::MAX_ATTACHMENTS,
+		)?;
 		let url = format!("channels/{}/messages/{}", self.channel_id, self.message_id);
-		self.client.patch(&url, self.em).await
+		if self.attachments.needs_existing() {
+			let existing = self.client.get(&url).await?;
+			self.attachments.keep_existing(existing);
+		}
+		if self.attachments.has_files() {
+			let multipart = self.attachments.multipart(self.em)?;
+			self.client.patch_multipart(&url, multipart, None).await
+		} else if !self.attachments.is_empty() {
+			let body = self.attachments.json(self.em)?;
+			self.client.patch(&url, body, None).await
+		} else {
+			self.client.patch(&url, self.em, None).await
+		}
 	}
 }
 
@@ -842,17 +1596,24 @@ pub struct CreateGuildBan<'a> {
 	client: &'a Client,
 	guild_id: GuildId,
 	user_id: UserId,
-	cgb: request::CreateGuildBan<'a>,
+	reason: Option<&'a str>,
+	cgb: body::CreateGuildBan,
 }
 
 impl<'a> CreateGuildBan<'a> {
-	pub fn delete_message_days(mut self, delete_message_days: u8) -> Self {
-		self.cgb.delete_message_days = Some(delete_message_days);
+	/// Sent as `delete_message_seconds`, `delete_message_days` is deprecated in API v10
+	pub fn delete_message_days(self, delete_message_days: u8) -> Self {
+		self.delete_message_seconds(delete_message_days as u32 * 24 * 3600)
+	}
+
+	/// Delete messages of the user sent in the last `seconds`, up to 7 days
+	pub fn delete_message_seconds(mut self, seconds: u32) -> Self {
+		self.cgb.delete_message_seconds = Some(seconds);
 		self
 	}
 
 	pub fn reason(mut self, reason: &'a str) -> Self {
-		self.cgb.reason = Some(reason);
+		self.reason = Some(reason);
 		self
 	}
 
@@ -861,6 +1622,7 @@ impl<'a> CreateGuildBan<'a> {
 			.put(
 				&format!("guilds/{}/bans/{}", self.guild_id, self.user_id),
 				self.cgb,
+				self.reason,
 			)
 			.await
 	}
@@ -973,6 +1735,14 @@ impl<'a> InteractionResponse<'a> {
 			}
 		}
 
+		if let Some(data) = &self.ir.data {
+			validate::message(
+				data.content,
+				data.embeds.as_deref().unwrap_or(&[]),
+				data.components.as_deref().unwrap_or(&[]),
+			)?;
+		}
+
 		self.client
 			.post_discard(
 				&format!(
@@ -980,6 +1750,7 @@ impl<'a> InteractionResponse<'a> {
 					self.interaction_id, self.token
 				),
 				self.ir,
+				None,
 			)
 			.await
 	}
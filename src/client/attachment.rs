@@ -0,0 +1,279 @@
+use super::{CowString, Error};
+use discord_types::request;
+use reqwest::multipart::{Form, Part};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use tokio::fs::File;
+
+/// File uploaded along with a message
+pub struct Attachment {
+	name: String,
+	description: Option<CowString>,
+	data: Data,
+}
+
+enum Data {
+	Bytes(Vec<u8>),
+	File(File, u64),
+}
+
+impl Attachment {
+	pub fn new<T: Into<String>, U: Into<Vec<u8>>>(name: T, data: U) -> Self {
+		Self {
+			name: name.into(),
+			description: None,
+			data: Data::Bytes(data.into()),
+		}
+	}
+
+	/// Stream the contents of `file` instead of loading it in memory
+	pub async fn file<T: Into<String>>(name: T, file: File) -> Result<Self, std::io::Error> {
+		let len = file.metadata().await?.len();
+		Ok(Self {
+			name: name.into(),
+			description: None,
+			data: Data::File(file, len),
+		})
+	}
+
+	/// Stream the file at `path`, named after its file name
+	pub async fn open<P: AsRef<Path>>(path: P) -> Result<Self, std::io::Error> {
+		let path = path.as_ref();
+		let name = path
+			.file_name()
+			.map(|n| n.to_string_lossy().into_owned())
+			.unwrap_or_else(|| "file".into());
+		Self::file(name, File::open(path).await?).await
+	}
+
+	/// Alt text, up to 1024 characters
+	pub fn description<T: Into<CowString>>(mut self, description: T) -> Self {
+		self.description = Some(description.into());
+		self
+	}
+
+	/// Blur the attachment until it is clicked
+	pub fn spoiler(mut self) -> Self {
+		if !self.name.starts_with("SPOILER_") {
+			self.name.insert_str(0, "SPOILER_");
+		}
+		self
+	}
+
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	fn part(self) -> Part {
+		let part = match self.data {
+			Data::Bytes(data) => Part::bytes(data),
+			Data::File(file, len) => Part::stream_with_length(file, len),
+		};
+		part.file_name(self.name)
+	}
+}
+
+impl From<request::Attachment> for Attachment {
+	fn from(attachment: request::Attachment) -> Self {
+		Self::new(attachment.name, attachment.data)
+	}
+}
+
+impl fmt::Debug for Attachment {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Attachment")
+			.field("name", &self.name)
+			.field("description", &self.description)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Entry of the `attachments` field of a message payload
+#[derive(Serialize)]
+struct AttachmentInfo<'a> {
+	id: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	filename: Option<&'a str>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	description: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct WithAttachments<'a, T> {
+	#[serde(flatten)]
+	payload: T,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	attachments: Option<Vec<AttachmentInfo<'a>>>,
+}
+
+/// Attachments of an existing message, only their ids are needed to keep them
+#[derive(Deserialize)]
+pub(crate) struct ExistingAttachments {
+	attachments: Vec<ExistingAttachment>,
+}
+
+#[derive(Deserialize)]
+struct ExistingAttachment {
+	id: String,
+}
+
+/// New files of a message, along with the existing attachments to keep when editing
+#[derive(Debug, Default)]
+pub(crate) struct Attachments {
+	files: Vec<Attachment>,
+	keep: Option<Vec<String>>,
+}
+
+impl Attachments {
+	pub(crate) fn push(&mut self, attachment: Attachment) {
+		self.files.push(attachment);
+	}
+
+	pub(crate) fn keep(&mut self, attachment_id: String) {
+		self.keep.get_or_insert_with(Vec::new).push(attachment_id);
+	}
+
+	/// Remove all existing attachments that aren't explicitly kept
+	pub(crate) fn remove_existing(&mut self) {
+		self.keep.get_or_insert_with(Vec::new);
+	}
+
+	/// Whether the ids of the existing attachments must be sent so they aren't removed,
+	/// Discord only keeps the attachments listed in the payload
+	pub(crate) fn needs_existing(&self) -> bool {
+		self.has_files() && self.keep.is_none()
+	}
+
+	pub(crate) fn keep_existing(&mut self, existing: ExistingAttachments) {
+		let keep = self.keep.get_or_insert_with(Vec::new);
+		keep.extend(existing.attachments.into_iter().map(|a| a.id));
+	}
+
+	pub(crate) fn len(&self) -> usize {
+		self.files.len()
+	}
+
+	pub(crate) fn has_files(&self) -> bool {
+		!self.files.is_empty()
+	}
+
+	pub(crate) fn is_empty(&self) -> bool {
+		self.files.is_empty() && self.keep.is_none()
+	}
+
+	fn info(&self) -> Option<Vec<AttachmentInfo<'_>>> {
+		if self.is_empty() {
+			return None;
+		}
+		let kept = self.keep.iter().flatten().map(|id| AttachmentInfo {
+			id: id.clone(),
+			filename: None,
+			description: None,
+		});
+		let new = self.files.iter().enumerate().map(|(i, a)| AttachmentInfo {
+			id: i.to_string(),
+			filename: Some(&a.name),
+			description: a.description.as_deref(),
+		});
+		Some(kept.chain(new).collect())
+	}
+
+	/// JSON body, for when there are no files to upload
+	pub(crate) fn json<T: Serialize>(&self, payload: T) -> Result<serde_json::Value, Error> {
+		let body = serde_json::to_value(WithAttachments {
+			payload,
+			attachments: self.info(),
+		})?;
+		Ok(body)
+	}
+
+	/// Multipart body with the payload and each file as `files[n]`
+	pub(crate) fn multipart<T: Serialize>(self, payload: T) -> Result<Form, Error> {
+		let payload = self.json(payload)?;
+		self.form(&payload)
+	}
+
+	/// Multipart body with `payload` as is, for payloads that already hold the attachment metadata
+	pub(crate) fn form<T: Serialize>(self, payload: &T) -> Result<Form, Error> {
+		let payload = serde_json::to_string(payload)?;
+		let mut form = Form::new().text("payload_json", payload);
+		for (i, attachment) in self.files.into_iter().enumerate() {
+			form = form.part(format!("files[{}]", i), attachment.part());
+		}
+		Ok(form)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Serialize)]
+	struct Payload {
+		content: &'static str,
+	}
+
+	#[test]
+	fn attachment_info() {
+		let mut attachments = Attachments::default();
+		assert_eq!(
+			attachments.json(Payload { content: "a" }).unwrap(),
+			serde_json::json!({"content": "a"})
+		);
+
+		attachments.keep("1234".into());
+		attachments.push(
+			Attachment::new("a.png", vec![])
+				.description("Alt")
+				.spoiler(),
+		);
+		assert_eq!(
+			attachments.json(Payload { content: "a" }).unwrap(),
+			serde_json::json!({
+				"content": "a",
+				"attachments": [
+					{"id": "1234"},
+					{"id": "0", "filename": "SPOILER_a.png", "description": "Alt"},
+				],
+			})
+		);
+	}
+
+	#[test]
+	fn edit_keeps_existing() {
+		let mut attachments = Attachments::default();
+		attachments.push(Attachment::new("b.png", vec![]));
+		assert!(attachments.needs_existing());
+
+		let existing = serde_json::from_value(serde_json::json!({
+			"id": "5678",
+			"attachments": [{"id": "1234", "filename": "a.png"}],
+		}))
+		.unwrap();
+		attachments.keep_existing(existing);
+		assert!(!attachments.needs_existing());
+		assert_eq!(
+			attachments.json(Payload { content: "a" }).unwrap(),
+			serde_json::json!({
+				"content": "a",
+				"attachments": [
+					{"id": "1234"},
+					{"id": "0", "filename": "b.png"},
+				],
+			})
+		);
+
+		let mut attachments = Attachments::default();
+		attachments.remove_existing();
+		attachments.push(Attachment::new("b.png", vec![]));
+		assert!(!attachments.needs_existing());
+		assert_eq!(
+			attachments.json(Payload { content: "a" }).unwrap(),
+			serde_json::json!({
+				"content": "a",
+				"attachments": [{"id": "0", "filename": "b.png"}],
+			})
+		);
+	}
+}
@@ -0,0 +1,529 @@
+use super::pagination::{paginate, snowflake_timestamp};
+use super::{Client, Error};
+use discord_types::{ChannelId, GuildId, MessageId, Permissions, RoleId, User, UserId};
+use futures::Stream;
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::Deserialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+macro_rules! audit_log_events {
+	($($name:ident = $value:expr,)*) => {
+		/// Type of action that resulted in an audit log entry
+		#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+		pub enum AuditLogEvent {
+			$($name,)*
+			Unknown(u16),
+		}
+
+		impl From<u16> for AuditLogEvent {
+			fn from(value: u16) -> Self {
+				match value {
+					$($value => AuditLogEvent::$name,)*
+					v => AuditLogEvent::Unknown(v),
+				}
+			}
+		}
+
+		impl From<AuditLogEvent> for u16 {
+			fn from(event: AuditLogEvent) -> Self {
+				match event {
+					$(AuditLogEvent::$name => $value,)*
+					AuditLogEvent::Unknown(v) => v,
+				}
+			}
+		}
+	};
+}
+
+audit_log_events! {
+	GuildUpdate = 1,
+	ChannelCreate = 10,
+	ChannelUpdate = 11,
+	ChannelDelete = 12,
+	ChannelOverwriteCreate = 13,
+	ChannelOverwriteUpdate = 14,
+	ChannelOverwriteDelete = 15,
+	MemberKick = 20,
+	MemberPrune = 21,
+	MemberBanAdd = 22,
+	MemberBanRemove = 23,
+	MemberUpdate = 24,
+	MemberRoleUpdate = 25,
+	MemberMove = 26,
+	MemberDisconnect = 27,
+	BotAdd = 28,
+	RoleCreate = 30,
+	RoleUpdate = 31,
+	RoleDelete = 32,
+	InviteCreate = 40,
+	InviteUpdate = 41,
+	InviteDelete = 42,
+	WebhookCreate = 50,
+	WebhookUpdate = 51,
+	WebhookDelete = 52,
+	EmojiCreate = 60,
+	EmojiUpdate = 61,
+	EmojiDelete = 62,
+	MessageDelete = 72,
+	MessageBulkDelete = 73,
+	MessagePin = 74,
+	MessageUnpin = 75,
+	IntegrationCreate = 80,
+	IntegrationUpdate = 81,
+	IntegrationDelete = 82,
+	StageInstanceCreate = 83,
+	StageInstanceUpdate = 84,
+	StageInstanceDelete = 85,
+	StickerCreate = 90,
+	StickerUpdate = 91,
+	StickerDelete = 92,
+	GuildScheduledEventCreate = 100,
+	GuildScheduledEventUpdate = 101,
+	GuildScheduledEventDelete = 102,
+	ThreadCreate = 110,
+	ThreadUpdate = 111,
+	ThreadDelete = 112,
+	ApplicationCommandPermissionUpdate = 121,
+	AutoModerationRuleCreate = 140,
+	AutoModerationRuleUpdate = 141,
+	AutoModerationRuleDelete = 142,
+	AutoModerationBlockMessage = 143,
+	AutoModerationFlagToChannel = 144,
+	AutoModerationUserCommunicationDisabled = 145,
+}
+
+impl<'de> Deserialize<'de> for AuditLogEvent {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		u16::deserialize(deserializer).map(Into::into)
+	}
+}
+
+macro_rules! snowflake_ids {
+	($($(#[$doc:meta])* $name:ident,)*) => {
+		$(
+			$(#[$doc])*
+			#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+			pub struct $name(pub u64);
+
+			impl $name {
+				/// Creation time, in milliseconds since the Unix epoch
+				pub fn timestamp(&self) -> u64 {
+					snowflake_timestamp(self.0)
+				}
+			}
+
+			impl fmt::Display for $name {
+				fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+					fmt::Display::fmt(&self.0, f)
+				}
+			}
+
+			impl<'de> Deserialize<'de> for $name {
+				fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+					let id = Cow::<str>::deserialize(deserializer)?;
+					id.parse().map($name).map_err(de::Error::custom)
+				}
+			}
+		)*
+	};
+}
+
+snowflake_ids! {
+	/// Id of an audit log entry, entries are ordered by it
+	AuditLogEntryId,
+	/// Id of an entity of any type, e.g. the target of an audit log entry
+	Snowflake,
+}
+
+impl Snowflake {
+	/// As a typed id, e.g. `UserId` for the target of a `MemberKick`
+	pub fn cast<T: DeserializeOwned>(&self) -> Option<T> {
+		T::deserialize(Value::String(self.0.to_string())).ok()
+	}
+}
+
+/// Value of a field before and after the action, `None` if it was unset
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change<T> {
+	pub old: Option<T>,
+	pub new: Option<T>,
+}
+
+impl<T: DeserializeOwned> Change<T> {
+	fn parse(raw: &RawChange) -> Option<Self> {
+		let parse = |value: &Option<Value>| match value {
+			None | Some(Value::Null) => Some(None),
+			Some(value) => T::deserialize(value).ok().map(Some),
+		};
+		Some(Change {
+			old: parse(&raw.old_value)?,
+			new: parse(&raw.new_value)?,
+		})
+	}
+}
+
+/// Role added to or removed from a member
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct AuditLogRole {
+	pub id: RoleId,
+	pub name: String,
+}
+
+#[derive(Deserialize)]
+struct RawChange {
+	key: String,
+	old_value: Option<Value>,
+	new_value: Option<Value>,
+}
+
+macro_rules! audit_log_changes {
+	($($name:ident($t:ty) = $key:expr,)*) => {
+		/// Field changed by the action of an entry
+		#[derive(Clone, Debug, PartialEq)]
+		pub enum AuditLogChange {
+			$($name(Change<$t>),)*
+			/// Field that isn't known, or whose values have an unexpected type
+			Other {
+				key: String,
+				old_value: Option<Value>,
+				new_value: Option<Value>,
+			},
+		}
+
+		impl AuditLogChange {
+			/// Name of the field as sent by Discord, e.g. `nick` or `$add`
+			pub fn key(&self) -> &str {
+				match self {
+					$(AuditLogChange::$name(_) => $key,)*
+					AuditLogChange::Other { key, .. } => key,
+				}
+			}
+
+			fn from_raw(raw: RawChange) -> Self {
+				let change = match raw.key.as_str() {
+					$($key => Change::parse(&raw).map(AuditLogChange::$name),)*
+					_ => None,
+				};
+				change.unwrap_or(AuditLogChange::Other {
+					key: raw.key,
+					old_value: raw.old_value,
+					new_value: raw.new_value,
+				})
+			}
+		}
+	};
+}
+
+audit_log_changes! {
+	Name(String) = "name",
+	Description(String) = "description",
+	Nick(String) = "nick",
+	Topic(String) = "topic",
+	IconHash(String) = "icon_hash",
+	AvatarHash(String) = "avatar_hash",
+	OwnerId(UserId) = "owner_id",
+	Permissions(Permissions) = "permissions",
+	Allow(Permissions) = "allow",
+	Deny(Permissions) = "deny",
+	Color(u32) = "color",
+	Hoist(bool) = "hoist",
+	Mentionable(bool) = "mentionable",
+	Position(i32) = "position",
+	Nsfw(bool) = "nsfw",
+	RateLimitPerUser(u32) = "rate_limit_per_user",
+	Bitrate(u32) = "bitrate",
+	UserLimit(u32) = "user_limit",
+	ParentId(ChannelId) = "parent_id",
+	ChannelId(ChannelId) = "channel_id",
+	CommunicationDisabledUntil(String) = "communication_disabled_until",
+	Deaf(bool) = "deaf",
+	Mute(bool) = "mute",
+	RolesAdded(Vec<AuditLogRole>) = "$add",
+	RolesRemoved(Vec<AuditLogRole>) = "$remove",
+	Code(String) = "code",
+	MaxUses(u32) = "max_uses",
+	MaxAge(u32) = "max_age",
+	Uses(u32) = "uses",
+	Temporary(bool) = "temporary",
+	Archived(bool) = "archived",
+	Locked(bool) = "locked",
+	Invitable(bool) = "invitable",
+	AutoArchiveDuration(u32) = "auto_archive_duration",
+}
+
+impl<'de> Deserialize<'de> for AuditLogChange {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		RawChange::deserialize(deserializer).map(AuditLogChange::from_raw)
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AuditLog {
+	pub audit_log_entries: Vec<AuditLogEntry>,
+	#[serde(default)]
+	pub users: Vec<User>,
+}
+
+impl AuditLog {
+	pub fn user(&self, user_id: UserId) -> Option<&User> {
+		self.users.iter().find(|u| u.id == user_id)
+	}
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct AuditLogEntry {
+	pub id: AuditLogEntryId,
+	/// Id of the affected entity, e.g. the user for `MemberKick` or the role for `RoleUpdate`
+	pub target_id: Option<Snowflake>,
+	/// User that performed the action
+	pub user_id: Option<UserId>,
+	pub action_type: AuditLogEvent,
+	#[serde(default)]
+	pub changes: Vec<AuditLogChange>,
+	pub options: Option<AuditLogOptions>,
+	pub reason: Option<String>,
+}
+
+impl AuditLogEntry {
+	pub fn is_target<T: fmt::Display>(&self, id: T) -> bool {
+		self.target_id.map(|t| t.to_string()) == Some(id.to_string())
+	}
+
+	/// Target of member actions, like kicks, bans and member updates
+	pub fn target_user(&self) -> Option<UserId> {
+		self.target_id?.cast()
+	}
+
+	pub fn target_role(&self) -> Option<RoleId> {
+		self.target_id?.cast()
+	}
+
+	pub fn target_channel(&self) -> Option<ChannelId> {
+		self.target_id?.cast()
+	}
+
+	pub fn change(&self, key: &str) -> Option<&AuditLogChange> {
+		self.changes.iter().find(|c| c.key() == key)
+	}
+
+	/// Creation time of the entry, in milliseconds since the Unix epoch
+	pub fn timestamp(&self) -> u64 {
+		self.id.timestamp()
+	}
+}
+
+/// Additional information for certain event types
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct AuditLogOptions {
+	pub channel_id: Option<ChannelId>,
+	pub count: Option<String>,
+	pub delete_member_days: Option<String>,
+	pub members_removed: Option<String>,
+	pub message_id: Option<MessageId>,
+	/// Id of the role or member of an overwrite
+	pub id: Option<Snowflake>,
+	#[serde(rename = "type")]
+	pub overwrite_type: Option<String>,
+	pub role_name: Option<String>,
+}
+
+impl Client {
+	pub fn get_guild_audit_log(&self, guild_id: GuildId) -> GetGuildAuditLog<'_> {
+		GetGuildAuditLog {
+			client: self,
+			guild_id,
+			user_id: None,
+			action_type: None,
+			before: None,
+			after: None,
+			limit: None,
+		}
+	}
+
+	/// Most recent kick or ban of `user_id`, to attribute a `GuildMemberRemove` event.
+	/// Entries older than `max_age` are ignored, so an earlier kick isn't attributed to a
+	/// later leave. The entry is created right before the event is sent, but a few seconds
+	/// of leeway are needed for clock skew
+	pub async fn find_member_removal(
+		&self,
+		guild_id: GuildId,
+		user_id: UserId,
+		max_age: Duration,
+	) -> Result<Option<AuditLogEntry>, Error> {
+		let min_timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_millis() as u64)
+			.unwrap_or(0)
+			.saturating_sub(max_age.as_millis() as u64);
+		let mut entries = Vec::new();
+		for action in [AuditLogEvent::MemberKick, AuditLogEvent::MemberBanAdd] {
+			let log = self
+				.get_guild_audit_log(guild_id)
+				.action_type(action)
+				.limit(10)
+				.send()
+				.await?;
+			entries.extend(
+				log.audit_log_entries
+					.into_iter()
+					.filter(|e| e.is_target(user_id) && e.timestamp() >= min_timestamp),
+			);
+		}
+		Ok(entries.into_iter().max_by_key(|e| e.id))
+	}
+}
+
+pub struct GetGuildAuditLog<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	user_id: Option<UserId>,
+	action_type: Option<AuditLogEvent>,
+	before: Option<String>,
+	after: Option<String>,
+	limit: Option<u8>,
+}
+
+impl<'a> GetGuildAuditLog<'a> {
+	/// Only entries of actions performed by this user
+	pub fn user(mut self, user_id: UserId) -> Self {
+		self.user_id = Some(user_id);
+		self
+	}
+
+	pub fn action_type(mut self, action_type: AuditLogEvent) -> Self {
+		self.action_type = Some(action_type);
+		self
+	}
+
+	pub fn before<T: fmt::Display>(mut self, entry_id: T) -> Self {
+		self.before = Some(entry_id.to_string());
+		self
+	}
+
+	pub fn after<T: fmt::Display>(mut self, entry_id: T) -> Self {
+		self.after = Some(entry_id.to_string());
+		self
+	}
+
+	/// Between 1 and 100, defaults to 50
+	pub fn limit(mut self, limit: u8) -> Self {
+		self.limit = Some(limit.clamp(1, 100));
+		self
+	}
+
+	fn url(&self, before: Option<&str>) -> String {
+		let mut url = format!(
+			"guilds/{}/audit-logs?limit={}",
+			self.guild_id,
+			self.limit.unwrap_or(50)
+		);
+		if let Some(user_id) = self.user_id {
+			url.push_str(&format!("&user_id={}", user_id));
+		}
+		if let Some(action_type) = self.action_type {
+			url.push_str(&format!("&action_type={}", u16::from(action_type)));
+		}
+		if let Some(before) = before.or(self.before.as_deref()) {
+			url.push_str(&format!("&before={}", before));
+		}
+		if let Some(after) = &self.after {
+			url.push_str(&format!("&after={}", after));
+		}
+		url
+	}
+
+	pub async fn send(self) -> Result<AuditLog, Error> {
+		self.client.get(&self.url(None)).await
+	}
+
+	/// Walk back through all matching entries, newest first.
+	/// The `after` filter still applies, `before` is only used for the first page
+	pub fn stream(self) -> impl Stream<Item = Result<AuditLogEntry, Error>> + 'a {
+		let limit = self.limit.unwrap_or(50) as usize;
+		paginate(None, move |before: Option<String>| {
+			let url = self.url(before.as_deref());
+			let client = self.client;
+			async move {
+				let log: AuditLog = client.get(&url).await?;
+				let next = if log.audit_log_entries.len() < limit {
+					None
+				} else {
+					log.audit_log_entries
+						.iter()
+						.map(|e| e.id)
+						.min()
+						.map(|id| id.to_string())
+				};
+				Ok((log.audit_log_entries, next))
+			}
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn entry() {
+		let entry: AuditLogEntry = serde_json::from_str(
+			r#"{
+				"id": "1100000000000000000",
+				"target_id": "80351110224678912",
+				"user_id": "81384788765712384",
+				"action_type": 24,
+				"changes": [{"key": "nick", "old_value": "foo", "new_value": "bar"}],
+				"reason": "Rename"
+			}"#,
+		)
+		.unwrap();
+
+		assert_eq!(entry.action_type, AuditLogEvent::MemberUpdate);
+		assert_eq!(entry.id, AuditLogEntryId(1100000000000000000));
+		assert!(entry.is_target(80351110224678912u64));
+		assert!(entry.target_user().is_some());
+		assert_eq!(
+			entry.change("nick"),
+			Some(&AuditLogChange::Nick(Change {
+				old: Some("foo".into()),
+				new: Some("bar".into()),
+			}))
+		);
+	}
+
+	#[test]
+	fn changes() {
+		let changes: Vec<AuditLogChange> = serde_json::from_str(
+			r#"[
+				{"key": "$add", "new_value": [{"id": "1234", "name": "Mod"}]},
+				{"key": "color", "old_value": "red", "new_value": 255},
+				{"key": "unknown", "new_value": 1}
+			]"#,
+		)
+		.unwrap();
+
+		match &changes[0] {
+			AuditLogChange::RolesAdded(Change {
+				old: None,
+				new: Some(roles),
+			}) => {
+				assert_eq!(roles[0].name, "Mod")
+			}
+			c => panic!("Unexpected change: {:?}", c),
+		}
+		// Values of an unexpected type are kept as is
+		assert!(matches!(changes[1], AuditLogChange::Other { .. }));
+		assert_eq!(changes[1].key(), "color");
+		assert_eq!(changes[2].key(), "unknown");
+	}
+
+	#[test]
+	fn unknown_event() {
+		let event: AuditLogEvent = serde_json::from_str("999").unwrap();
+		assert_eq!(event, AuditLogEvent::Unknown(999));
+		assert_eq!(u16::from(event), 999);
+	}
+}
@@ -0,0 +1,338 @@
+//! Request bodies of endpoints not covered by `discord_types::request`
+
+use super::{AutoArchiveDuration, CowString};
+use discord_types::{
+	AllowedMentions, ChannelId, ChannelType, Component, Embed, GuildId, MessageId, Permissions,
+	RoleId, UserId,
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub(crate) struct BulkDeleteMessages<'a> {
+	pub messages: &'a [MessageId],
+}
+
+#[derive(Serialize)]
+pub(crate) struct PermissionOverwrite {
+	pub id: String,
+	#[serde(rename = "type")]
+	pub overwrite_type: u8,
+	pub allow: Permissions,
+	pub deny: Permissions,
+}
+
+#[derive(Serialize)]
+pub(crate) struct EditChannelPermissions {
+	#[serde(rename = "type")]
+	pub overwrite_type: u8,
+	pub allow: Permissions,
+	pub deny: Permissions,
+}
+
+#[derive(Serialize)]
+pub(crate) struct CreateChannel {
+	pub name: CowString,
+	#[serde(rename = "type")]
+	pub channel_type: ChannelType,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub topic: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub position: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub parent_id: Option<ChannelId>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nsfw: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub rate_limit_per_user: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub bitrate: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub user_limit: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub permission_overwrites: Option<Vec<PermissionOverwrite>>,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct ModifyChannel {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub topic: Option<Option<CowString>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub position: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub parent_id: Option<Option<ChannelId>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nsfw: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub rate_limit_per_user: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub bitrate: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub user_limit: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub permission_overwrites: Option<Vec<PermissionOverwrite>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub archived: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub locked: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub invitable: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub auto_archive_duration: Option<AutoArchiveDuration>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ChannelPosition {
+	pub id: ChannelId,
+	pub position: u16,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub lock_permissions: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub parent_id: Option<Option<ChannelId>>,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct GuildRole {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub permissions: Option<Permissions>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub color: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub hoist: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub icon: Option<Option<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub unicode_emoji: Option<Option<CowString>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub mentionable: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RolePosition {
+	pub id: RoleId,
+	pub position: u16,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct ModifyGuildMember {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nick: Option<Option<CowString>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub roles: Option<Vec<RoleId>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub mute: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub deaf: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub channel_id: Option<Option<ChannelId>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub communication_disabled_until: Option<Option<String>>,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct CreateGuildBan {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub delete_message_seconds: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct BulkGuildBan<'a> {
+	pub user_ids: &'a [UserId],
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub delete_message_seconds: Option<u32>,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct CreateMessage {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub content: Option<CowString>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub embeds: Vec<Embed>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub components: Vec<Component>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub sticker_ids: Vec<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tts: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub enforce_nonce: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub message_reference: Option<MessageReference>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub allowed_mentions: Option<AllowedMentions>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub flags: Option<u64>,
+	/// Applied to `message_reference` whenever it is set
+	#[serde(skip)]
+	pub fail_if_not_exists: Option<bool>,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct MessageReference {
+	pub message_id: MessageId,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub channel_id: Option<ChannelId>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub guild_id: Option<GuildId>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fail_if_not_exists: Option<bool>,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct EditMessage {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub content: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub embeds: Option<Vec<Embed>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub components: Option<Vec<Component>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub allowed_mentions: Option<AllowedMentions>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub flags: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct StartThread {
+	pub name: CowString,
+	#[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+	pub channel_type: Option<ChannelType>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub auto_archive_duration: Option<AutoArchiveDuration>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub rate_limit_per_user: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub invitable: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub applied_tags: Option<Vec<String>>,
+	/// First message of a forum post, along with its attachments
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub message: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct CreateWebhook {
+	pub name: CowString,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub avatar: Option<String>,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct ModifyWebhook {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub avatar: Option<Option<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub channel_id: Option<ChannelId>,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct ExecuteWebhook {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub content: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub username: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub avatar_url: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tts: Option<bool>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub embeds: Vec<Embed>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub components: Vec<Component>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub allowed_mentions: Option<AllowedMentions>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub thread_name: Option<CowString>,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct EditWebhookMessage {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub content: Option<Option<CowString>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub embeds: Option<Vec<Embed>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub components: Option<Vec<Component>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub allowed_mentions: Option<AllowedMentions>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct CreateDm {
+	pub recipient_id: UserId,
+}
+
+#[derive(Default, Serialize)]
+pub(crate) struct ModifyCurrentUser {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub username: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub avatar: Option<Option<String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub banner: Option<Option<String>>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct AutocompleteResponse {
+	#[serde(rename = "type")]
+	pub response_type: u8,
+	pub data: AutocompleteData,
+}
+
+#[derive(Serialize)]
+pub(crate) struct AutocompleteData {
+	pub choices: Vec<Choice>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Choice {
+	pub name: CowString,
+	pub value: serde_json::Value,
+}
+
+/// Data URI of an image, as expected by avatar and icon fields
+pub(crate) fn image_data(mime_type: &str, data: &[u8]) -> String {
+	const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+	let mut out = format!("data:{};base64,", mime_type);
+	out.reserve(data.len().div_ceil(3) * 4);
+	for chunk in data.chunks(3) {
+		let b = [
+			chunk[0],
+			*chunk.get(1).unwrap_or(&0),
+			*chunk.get(2).unwrap_or(&0),
+		];
+		let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+		for i in 0..4 {
+			if i <= chunk.len() {
+				out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+			} else {
+				out.push('=');
+			}
+		}
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn image_data_uri() {
+		assert_eq!(image_data("image/png", b""), "data:image/png;base64,");
+		assert_eq!(image_data("image/png", b"f"), "data:image/png;base64,Zg==");
+		assert_eq!(image_data("image/png", b"fo"), "data:image/png;base64,Zm8=");
+		assert_eq!(
+			image_data("image/png", b"foobar"),
+			"data:image/png;base64,Zm9vYmFy"
+		);
+	}
+}
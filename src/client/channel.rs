@@ -0,0 +1,405 @@
+use super::{body, AuditRequest, AutoArchiveDuration, Client, CowString, Error};
+use discord_types::{Channel, ChannelId, ChannelType, GuildId, Permissions, RoleId, UserId};
+use reqwest::Method;
+
+/// Target of a permission overwrite
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overwrite {
+	Role(RoleId),
+	Member(UserId),
+}
+
+impl Overwrite {
+	fn id(&self) -> String {
+		match self {
+			Overwrite::Role(id) => id.to_string(),
+			Overwrite::Member(id) => id.to_string(),
+		}
+	}
+
+	fn overwrite_type(&self) -> u8 {
+		match self {
+			Overwrite::Role(_) => 0,
+			Overwrite::Member(_) => 1,
+		}
+	}
+
+	fn body(&self, allow: Permissions, deny: Permissions) -> body::PermissionOverwrite {
+		body::PermissionOverwrite {
+			id: self.id(),
+			overwrite_type: self.overwrite_type(),
+			allow,
+			deny,
+		}
+	}
+}
+
+impl From<RoleId> for Overwrite {
+	fn from(id: RoleId) -> Self {
+		Overwrite::Role(id)
+	}
+}
+
+impl From<UserId> for Overwrite {
+	fn from(id: UserId) -> Self {
+		Overwrite::Member(id)
+	}
+}
+
+impl Client {
+	pub fn create_channel<T: Into<CowString>>(
+		&self,
+		guild_id: GuildId,
+		name: T,
+		channel_type: ChannelType,
+	) -> CreateChannel<'_> {
+		CreateChannel {
+			client: self,
+			guild_id,
+			reason: None,
+			cc: body::CreateChannel {
+				name: name.into(),
+				channel_type,
+				topic: None,
+				position: None,
+				parent_id: None,
+				nsfw: None,
+				rate_limit_per_user: None,
+				bitrate: None,
+				user_limit: None,
+				permission_overwrites: None,
+			},
+		}
+	}
+
+	pub fn modify_channel(&self, channel_id: ChannelId) -> ModifyChannel<'_> {
+		ModifyChannel {
+			client: self,
+			channel_id,
+			reason: None,
+			mc: Default::default(),
+		}
+	}
+
+	pub fn delete_channel(&self, channel_id: ChannelId) -> AuditRequest<'_> {
+		AuditRequest::new(self, Method::DELETE, format!("channels/{}", channel_id))
+	}
+
+	pub fn edit_channel_permissions<T: Into<Overwrite>>(
+		&self,
+		channel_id: ChannelId,
+		target: T,
+		allow: Permissions,
+		deny: Permissions,
+	) -> EditChannelPermissions<'_> {
+		let target = target.into();
+		EditChannelPermissions {
+			client: self,
+			channel_id,
+			target,
+			reason: None,
+			ecp: body::EditChannelPermissions {
+				overwrite_type: target.overwrite_type(),
+				allow,
+				deny,
+			},
+		}
+	}
+
+	pub fn delete_channel_permission<T: Into<Overwrite>>(
+		&self,
+		channel_id: ChannelId,
+		target: T,
+	) -> AuditRequest<'_> {
+		AuditRequest::new(
+			self,
+			Method::DELETE,
+			format!("channels/{}/permissions/{}", channel_id, target.into().id()),
+		)
+	}
+
+	pub fn modify_channel_positions<I>(
+		&self,
+		guild_id: GuildId,
+		positions: I,
+	) -> ModifyChannelPositions<'_>
+	where
+		I: IntoIterator<Item = ChannelPosition>,
+	{
+		ModifyChannelPositions {
+			client: self,
+			guild_id,
+			reason: None,
+			positions: positions.into_iter().map(|p| p.body).collect(),
+		}
+	}
+}
+
+pub struct CreateChannel<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	reason: Option<&'a str>,
+	cc: body::CreateChannel,
+}
+
+impl<'a> CreateChannel<'a> {
+	pub fn topic<T: Into<CowString>>(mut self, topic: T) -> Self {
+		self.cc.topic = Some(topic.into());
+		self
+	}
+
+	pub fn position(mut self, position: u16) -> Self {
+		self.cc.position = Some(position);
+		self
+	}
+
+	pub fn parent(mut self, parent_id: ChannelId) -> Self {
+		self.cc.parent_id = Some(parent_id);
+		self
+	}
+
+	pub fn nsfw(mut self, nsfw: bool) -> Self {
+		self.cc.nsfw = Some(nsfw);
+		self
+	}
+
+	/// Slowmode, in seconds
+	pub fn rate_limit_per_user(mut self, seconds: u16) -> Self {
+		self.cc.rate_limit_per_user = Some(seconds);
+		self
+	}
+
+	pub fn bitrate(mut self, bitrate: u32) -> Self {
+		self.cc.bitrate = Some(bitrate);
+		self
+	}
+
+	pub fn user_limit(mut self, user_limit: u16) -> Self {
+		self.cc.user_limit = Some(user_limit);
+		self
+	}
+
+	pub fn permission_overwrite<T: Into<Overwrite>>(
+		mut self,
+		target: T,
+		allow: Permissions,
+		deny: Permissions,
+	) -> Self {
+		self.cc
+			.permission_overwrites
+			.get_or_insert_with(Vec::new)
+			.push(target.into().body(allow, deny));
+		self
+	}
+
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<Channel, Error> {
+		self.client
+			.post(
+				&format!("guilds/{}/channels", self.guild_id),
+				self.cc,
+				self.reason,
+			)
+			.await
+	}
+}
+
+pub struct ModifyChannel<'a> {
+	client: &'a Client,
+	channel_id: ChannelId,
+	reason: Option<&'a str>,
+	mc: body::ModifyChannel,
+}
+
+impl<'a> ModifyChannel<'a> {
+	pub fn name<T: Into<CowString>>(mut self, name: T) -> Self {
+		self.mc.name = Some(name.into());
+		self
+	}
+
+	pub fn topic<T: Into<CowString>>(mut self, topic: T) -> Self {
+		self.mc.topic = Some(Some(topic.into()));
+		self
+	}
+
+	pub fn clear_topic(mut self) -> Self {
+		self.mc.topic = Some(None);
+		self
+	}
+
+	pub fn position(mut self, position: u16) -> Self {
+		self.mc.position = Some(position);
+		self
+	}
+
+	pub fn parent(mut self, parent_id: ChannelId) -> Self {
+		self.mc.parent_id = Some(Some(parent_id));
+		self
+	}
+
+	/// Move the channel out of its category
+	pub fn clear_parent(mut self) -> Self {
+		self.mc.parent_id = Some(None);
+		self
+	}
+
+	pub fn nsfw(mut self, nsfw: bool) -> Self {
+		self.mc.nsfw = Some(nsfw);
+		self
+	}
+
+	/// Slowmode, in seconds. Zero disables it
+	pub fn rate_limit_per_user(mut self, seconds: u16) -> Self {
+		self.mc.rate_limit_per_user = Some(seconds);
+		self
+	}
+
+	pub fn bitrate(mut self, bitrate: u32) -> Self {
+		self.mc.bitrate = Some(bitrate);
+		self
+	}
+
+	pub fn user_limit(mut self, user_limit: u16) -> Self {
+		self.mc.user_limit = Some(user_limit);
+		self
+	}
+
+	/// Replace all permission overwrites of the channel
+	pub fn permission_overwrites<I, T>(mut self, overwrites: I) -> Self
+	where
+		I: IntoIterator<Item = (T, Permissions, Permissions)>,
+		T: Into<Overwrite>,
+	{
+		self.mc.permission_overwrites = Some(
+			overwrites
+				.into_iter()
+				.map(|(t, allow, deny)| t.into().body(allow, deny))
+				.collect(),
+		);
+		self
+	}
+
+	/// Archive or unarchive a thread
+	pub fn archived(mut self, archived: bool) -> Self {
+		self.mc.archived = Some(archived);
+		self
+	}
+
+	/// Only members with `MANAGE_THREADS` can unarchive a locked thread
+	pub fn locked(mut self, locked: bool) -> Self {
+		self.mc.locked = Some(locked);
+		self
+	}
+
+	/// Whether non-moderators can add other members to a private thread
+	pub fn invitable(mut self, invitable: bool) -> Self {
+		self.mc.invitable = Some(invitable);
+		self
+	}
+
+	pub fn auto_archive_duration(mut self, duration: AutoArchiveDuration) -> Self {
+		self.mc.auto_archive_duration = Some(duration);
+		self
+	}
+
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<Channel, Error> {
+		self.client
+			.patch(
+				&format!("channels/{}", self.channel_id),
+				self.mc,
+				self.reason,
+			)
+			.await
+	}
+}
+
+pub struct EditChannelPermissions<'a> {
+	client: &'a Client,
+	channel_id: ChannelId,
+	target: Overwrite,
+	reason: Option<&'a str>,
+	ecp: body::EditChannelPermissions,
+}
+
+impl<'a> EditChannelPermissions<'a> {
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<(), Error> {
+		self.client
+			.put(
+				&format!(
+					"channels/{}/permissions/{}",
+					self.channel_id,
+					self.target.id()
+				),
+				self.ecp,
+				self.reason,
+			)
+			.await
+	}
+}
+
+pub struct ChannelPosition {
+	body: body::ChannelPosition,
+}
+
+impl ChannelPosition {
+	pub fn new(channel_id: ChannelId, position: u16) -> Self {
+		Self {
+			body: body::ChannelPosition {
+				id: channel_id,
+				position,
+				lock_permissions: None,
+				parent_id: None,
+			},
+		}
+	}
+
+	/// Move the channel into a category
+	pub fn parent(mut self, parent_id: ChannelId) -> Self {
+		self.body.parent_id = Some(Some(parent_id));
+		self
+	}
+
+	/// Sync the permission overwrites with the new parent
+	pub fn lock_permissions(mut self) -> Self {
+		self.body.lock_permissions = Some(true);
+		self
+	}
+}
+
+pub struct ModifyChannelPositions<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	reason: Option<&'a str>,
+	positions: Vec<body::ChannelPosition>,
+}
+
+impl<'a> ModifyChannelPositions<'a> {
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<(), Error> {
+		self.client
+			.patch_discard(
+				&format!("guilds/{}/channels", self.guild_id),
+				self.positions,
+				self.reason,
+			)
+			.await
+	}
+}
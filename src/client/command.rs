@@ -0,0 +1,455 @@
+use super::{decode, json, Client, CowString, Error};
+use crate::interaction::{CommandArgs, OptionSpec};
+use discord_types::{
+	ApplicationCommand, ApplicationCommandOption, ApplicationId, ChannelId, GuildId, Permissions,
+	RoleId, UserId,
+};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum CommandType {
+	/// Slash command
+	ChatInput = 1,
+	/// Context menu entry on a user
+	User = 2,
+	/// Context menu entry on a message
+	Message = 3,
+}
+
+/// Definition of an application command, as sent when creating or overwriting commands
+#[derive(Clone, Debug, Serialize)]
+pub struct NewCommand {
+	name: CowString,
+	#[serde(rename = "type")]
+	command_type: CommandType,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	description: Option<CowString>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	options: Vec<NewOption>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	default_member_permissions: Option<Permissions>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	dm_permission: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	nsfw: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	name_localizations: Option<HashMap<String, String>>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	description_localizations: Option<HashMap<String, String>>,
+}
+
+/// Option of a `NewCommand`, only serialized when the command is sent
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+enum NewOption {
+	Option(ApplicationCommandOption),
+	Spec(OptionSpec),
+}
+
+impl NewCommand {
+	fn new(name: CowString, command_type: CommandType, description: Option<CowString>) -> Self {
+		Self {
+			name,
+			command_type,
+			description,
+			options: Vec::new(),
+			default_member_permissions: None,
+			dm_permission: None,
+			nsfw: None,
+			name_localizations: None,
+			description_localizations: None,
+		}
+	}
+
+	pub fn chat_input<T: Into<CowString>, U: Into<CowString>>(name: T, description: U) -> Self {
+		Self::new(
+			name.into(),
+			CommandType::ChatInput,
+			Some(description.into()),
+		)
+	}
+
+	pub fn user<T: Into<CowString>>(name: T) -> Self {
+		Self::new(name.into(), CommandType::User, None)
+	}
+
+	pub fn message<T: Into<CowString>>(name: T) -> Self {
+		Self::new(name.into(), CommandType::Message, None)
+	}
+
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	pub fn command_type(&self) -> CommandType {
+		self.command_type
+	}
+
+	/// Only for chat input commands
+	pub fn option(mut self, option: ApplicationCommandOption) -> Self {
+		self.options.push(NewOption::Option(option));
+		self
+	}
+
+	pub fn options<I: IntoIterator<Item = ApplicationCommandOption>>(mut self, options: I) -> Self {
+		self.options
+			.extend(options.into_iter().map(NewOption::Option));
+		self
+	}
+
+	/// Replace the options with those of `T`, see `interaction::Command`
+	pub fn args<T: CommandArgs>(mut self) -> Self {
+		self.options = T::options().into_iter().map(NewOption::Spec).collect();
+		self
+	}
+
+	/// Permissions a member needs to see the command, until overridden by the guild.
+	/// `Permissions::empty()` restricts it to administrators
+	pub fn default_member_permissions(mut self, permissions: Permissions) -> Self {
+		self.default_member_permissions = Some(permissions);
+		self
+	}
+
+	/// Whether a global command is available in DMs with the bot
+	pub fn dm_permission(mut self, dm_permission: bool) -> Self {
+		self.dm_permission = Some(dm_permission);
+		self
+	}
+
+	/// Only show the command in age-restricted channels
+	pub fn nsfw(mut self, nsfw: bool) -> Self {
+		self.nsfw = Some(nsfw);
+		self
+	}
+
+	/// Name shown to users with the given locale, e.g. `"fr"` or `"en-GB"`
+	pub fn name_localization<T: Into<String>, U: Into<String>>(
+		mut self,
+		locale: T,
+		name: U,
+	) -> Self {
+		self.name_localizations
+			.get_or_insert_with(HashMap::new)
+			.insert(locale.into(), name.into());
+		self
+	}
+
+	pub fn description_localization<T: Into<String>, U: Into<String>>(
+		mut self,
+		locale: T,
+		description: U,
+	) -> Self {
+		self.description_localizations
+			.get_or_insert_with(HashMap::new)
+			.insert(locale.into(), description.into());
+		self
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum CommandPermissionType {
+	Role = 1,
+	User = 2,
+	Channel = 3,
+}
+
+/// Override of who can use a command in a guild
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandPermission {
+	/// Id of the role, user or channel
+	pub id: String,
+	#[serde(rename = "type")]
+	pub permission_type: CommandPermissionType,
+	/// Whether the command is allowed or denied
+	pub permission: bool,
+}
+
+impl CommandPermission {
+	pub fn role(role_id: RoleId, permission: bool) -> Self {
+		Self {
+			id: role_id.to_string(),
+			permission_type: CommandPermissionType::Role,
+			permission,
+		}
+	}
+
+	pub fn user(user_id: UserId, permission: bool) -> Self {
+		Self {
+			id: user_id.to_string(),
+			permission_type: CommandPermissionType::User,
+			permission,
+		}
+	}
+
+	pub fn channel(channel_id: ChannelId, permission: bool) -> Self {
+		Self {
+			id: channel_id.to_string(),
+			permission_type: CommandPermissionType::Channel,
+			permission,
+		}
+	}
+
+	/// Applies to all members, `@everyone` has the id of the guild
+	pub fn everyone(guild_id: GuildId, permission: bool) -> Self {
+		Self {
+			id: guild_id.to_string(),
+			permission_type: CommandPermissionType::Role,
+			permission,
+		}
+	}
+}
+
+/// Permission overrides of a command in a guild
+#[derive(Clone, Debug, Deserialize)]
+pub struct GuildCommandPermissions {
+	/// Id of the command, or of the application for overrides of all its commands
+	pub id: String,
+	pub application_id: ApplicationId,
+	pub guild_id: GuildId,
+	pub permissions: Vec<CommandPermission>,
+}
+
+#[derive(Serialize)]
+struct EditCommandPermissions<'a> {
+	permissions: &'a [CommandPermission],
+}
+
+pub(super) fn commands_url(application_id: ApplicationId, guild_id: Option<GuildId>) -> String {
+	match guild_id {
+		Some(guild_id) => format!(
+			"applications/{}/guilds/{}/commands",
+			application_id, guild_id
+		),
+		None => format!("applications/{}/commands", application_id),
+	}
+}
+
+impl Client {
+	pub async fn get_global_commands(
+		&self,
+		application_id: ApplicationId,
+	) -> Result<Vec<ApplicationCommand>, Error> {
+		self.get(&commands_url(application_id, None)).await
+	}
+
+	pub async fn get_global_command<T: fmt::Display>(
+		&self,
+		application_id: ApplicationId,
+		command_id: T,
+	) -> Result<ApplicationCommand, Error> {
+		self.get(&format!(
+			"{}/{}",
+			commands_url(application_id, None),
+			command_id
+		))
+		.await
+	}
+
+	/// Replaces an existing global command with the same name and type
+	pub async fn create_global_command(
+		&self,
+		application_id: ApplicationId,
+		command: &NewCommand,
+	) -> Result<ApplicationCommand, Error> {
+		self.post(&commands_url(application_id, None), command, None)
+			.await
+	}
+
+	pub async fn edit_global_command<T: fmt::Display>(
+		&self,
+		application_id: ApplicationId,
+		command_id: T,
+		command: &NewCommand,
+	) -> Result<ApplicationCommand, Error> {
+		self.patch(
+			&format!("{}/{}", commands_url(application_id, None), command_id),
+			command,
+			None,
+		)
+		.await
+	}
+
+	pub async fn delete_global_command<T: fmt::Display>(
+		&self,
+		application_id: ApplicationId,
+		command_id: T,
+	) -> Result<(), Error> {
+		self.delete(
+			&format!("{}/{}", commands_url(application_id, None), command_id),
+			None,
+		)
+		.await
+	}
+
+	/// Replace all global commands, commands that aren't in `commands` are deleted
+	pub async fn bulk_overwrite_global_commands(
+		&self,
+		application_id: ApplicationId,
+		commands: &[NewCommand],
+	) -> Result<Vec<ApplicationCommand>, Error> {
+		self.bulk_overwrite(commands_url(application_id, None), commands)
+			.await
+	}
+
+	pub async fn get_guild_commands(
+		&self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+	) -> Result<Vec<ApplicationCommand>, Error> {
+		self.get(&commands_url(application_id, Some(guild_id)))
+			.await
+	}
+
+	pub async fn get_guild_command<T: fmt::Display>(
+		&self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+		command_id: T,
+	) -> Result<ApplicationCommand, Error> {
+		self.get(&format!(
+			"{}/{}",
+			commands_url(application_id, Some(guild_id)),
+			command_id
+		))
+		.await
+	}
+
+	/// Replaces an existing guild command with the same name and type
+	pub async fn create_guild_command(
+		&self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+		command: &NewCommand,
+	) -> Result<ApplicationCommand, Error> {
+		self.post(&commands_url(application_id, Some(guild_id)), command, None)
+			.await
+	}
+
+	pub async fn edit_guild_command<T: fmt::Display>(
+		&self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+		command_id: T,
+		command: &NewCommand,
+	) -> Result<ApplicationCommand, Error> {
+		self.patch(
+			&format!(
+				"{}/{}",
+				commands_url(application_id, Some(guild_id)),
+				command_id
+			),
+			command,
+			None,
+		)
+		.await
+	}
+
+	pub async fn delete_guild_command<T: fmt::Display>(
+		&self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+		command_id: T,
+	) -> Result<(), Error> {
+		self.delete(
+			&format!(
+				"{}/{}",
+				commands_url(application_id, Some(guild_id)),
+				command_id
+			),
+			None,
+		)
+		.await
+	}
+
+	/// Replace all commands of the guild, commands that aren't in `commands` are deleted
+	pub async fn bulk_overwrite_guild_commands(
+		&self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+		commands: &[NewCommand],
+	) -> Result<Vec<ApplicationCommand>, Error> {
+		self.bulk_overwrite(commands_url(application_id, Some(guild_id)), commands)
+			.await
+	}
+
+	async fn bulk_overwrite(
+		&self,
+		url: String,
+		commands: &[NewCommand],
+	) -> Result<Vec<ApplicationCommand>, Error> {
+		decode(self.send(Method::PUT, &url, |r| json(r, &commands)).await?).await
+	}
+
+	/// Permission overrides of all commands of the application in the guild
+	pub async fn get_guild_command_permissions(
+		&self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+	) -> Result<Vec<GuildCommandPermissions>, Error> {
+		self.get(&format!(
+			"{}/permissions",
+			commands_url(application_id, Some(guild_id))
+		))
+		.await
+	}
+
+	pub async fn get_command_permissions<T: fmt::Display>(
+		&self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+		command_id: T,
+	) -> Result<GuildCommandPermissions, Error> {
+		self.get(&format!(
+			"{}/{}/permissions",
+			commands_url(application_id, Some(guild_id)),
+			command_id
+		))
+		.await
+	}
+
+	/// Replace the permission overrides of a command. Discord only accepts this with a
+	/// bearer token that has the `applications.commands.permissions.update` scope,
+	/// see `ClientBuilder::bearer`
+	pub async fn edit_command_permissions<T: fmt::Display>(
+		&self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+		command_id: T,
+		permissions: &[CommandPermission],
+	) -> Result<GuildCommandPermissions, Error> {
+		let url = format!(
+			"{}/{}/permissions",
+			commands_url(application_id, Some(guild_id)),
+			command_id
+		);
+		let body = EditCommandPermissions { permissions };
+		decode(self.send(Method::PUT, &url, |r| json(r, &body)).await?).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn context_menu_body() {
+		let command = NewCommand::user("Report")
+			.dm_permission(false)
+			.name_localization("fr", "Signaler");
+		assert_eq!(
+			serde_json::to_value(&command).unwrap(),
+			serde_json::json!({
+				"name": "Report",
+				"type": 2,
+				"dm_permission": false,
+				"name_localizations": {"fr": "Signaler"},
+			})
+		);
+	}
+}
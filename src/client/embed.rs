@@ -0,0 +1,246 @@
+use super::{validate, Attachment, CowString, Error};
+use chrono::{DateTime, Utc};
+use discord_types::Embed;
+use serde::Serialize;
+
+#[derive(Clone, Debug, Default, Serialize)]
+struct EmbedData {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	title: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	description: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	url: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	timestamp: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	color: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	footer: Option<Footer>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	image: Option<Media>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	thumbnail: Option<Media>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	author: Option<Author>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	fields: Vec<Field>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Footer {
+	text: CowString,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	icon_url: Option<CowString>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Media {
+	url: CowString,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Author {
+	name: CowString,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	url: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	icon_url: Option<CowString>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Field {
+	name: CowString,
+	value: CowString,
+	inline: bool,
+}
+
+/// Builds an `Embed`, checking Discord's limits on the length of its text
+#[derive(Clone, Debug, Default)]
+pub struct EmbedBuilder {
+	embed: EmbedData,
+}
+
+impl EmbedBuilder {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Up to 256 characters
+	pub fn title<T: Into<CowString>>(mut self, title: T) -> Self {
+		self.embed.title = Some(title.into());
+		self
+	}
+
+	/// Up to 4096 characters
+	pub fn description<T: Into<CowString>>(mut self, description: T) -> Self {
+		self.embed.description = Some(description.into());
+		self
+	}
+
+	/// Link of the title
+	pub fn url<T: Into<CowString>>(mut self, url: T) -> Self {
+		self.embed.url = Some(url.into());
+		self
+	}
+
+	pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+		self.embed.timestamp = Some(timestamp.to_rfc3339());
+		self
+	}
+
+	/// RGB color of the border, e.g. `0x5865f2`
+	pub fn color(mut self, color: u32) -> Self {
+		self.embed.color = Some(color & 0xffffff);
+		self
+	}
+
+	/// Up to 2048 characters
+	pub fn footer<T: Into<CowString>>(mut self, text: T) -> Self {
+		self.embed.footer = Some(Footer {
+			text: text.into(),
+			icon_url: None,
+		});
+		self
+	}
+
+	pub fn footer_icon<T: Into<CowString>, U: Into<CowString>>(
+		mut self,
+		text: T,
+		icon_url: U,
+	) -> Self {
+		self.embed.footer = Some(Footer {
+			text: text.into(),
+			icon_url: Some(icon_url.into()),
+		});
+		self
+	}
+
+	pub fn image<T: Into<CowString>>(mut self, url: T) -> Self {
+		self.embed.image = Some(Media { url: url.into() });
+		self
+	}
+
+	/// Show an attachment of the same message as the image
+	pub fn image_attachment(self, attachment: &Attachment) -> Self {
+		self.image(attachment_url(attachment))
+	}
+
+	pub fn thumbnail<T: Into<CowString>>(mut self, url: T) -> Self {
+		self.embed.thumbnail = Some(Media { url: url.into() });
+		self
+	}
+
+	/// Show an attachment of the same message as the thumbnail
+	pub fn thumbnail_attachment(self, attachment: &Attachment) -> Self {
+		self.thumbnail(attachment_url(attachment))
+	}
+
+	/// Up to 256 characters
+	pub fn author<T: Into<CowString>>(mut self, name: T) -> Self {
+		self.embed.author = Some(Author {
+			name: name.into(),
+			url: None,
+			icon_url: None,
+		});
+		self
+	}
+
+	/// Link of the author's name, requires `author` to be set first
+	pub fn author_url<T: Into<CowString>>(mut self, url: T) -> Self {
+		if let Some(author) = &mut self.embed.author {
+			author.url = Some(url.into());
+		}
+		self
+	}
+
+	/// Requires `author` to be set first
+	pub fn author_icon<T: Into<CowString>>(mut self, icon_url: T) -> Self {
+		if let Some(author) = &mut self.embed.author {
+			author.icon_url = Some(icon_url.into());
+		}
+		self
+	}
+
+	/// Up to 25 fields, with a name of up to 256 and a value of up to 1024 characters
+	pub fn field<T: Into<CowString>, U: Into<CowString>>(mut self, name: T, value: U) -> Self {
+		self.embed.fields.push(Field {
+			name: name.into(),
+			value: value.into(),
+			inline: false,
+		});
+		self
+	}
+
+	/// Field displayed next to the other inline fields around it
+	pub fn inline_field<T: Into<CowString>, U: Into<CowString>>(
+		mut self,
+		name: T,
+		value: U,
+	) -> Self {
+		self.embed.fields.push(Field {
+			name: name.into(),
+			value: value.into(),
+			inline: true,
+		});
+		self
+	}
+
+	/// Check the limits, including the 6000 characters across all text of the embed
+	pub fn build(self) -> Result<Embed, Error> {
+		let embed = serde_json::to_value(self.embed)?;
+		let total = validate::embed_len(&embed)?;
+		validate::embed_total("embed", total)?;
+		Ok(serde_json::from_value(embed)?)
+	}
+}
+
+fn attachment_url(attachment: &Attachment) -> String {
+	format!("attachment://{}", attachment.name())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::client::ValidationError;
+
+	#[test]
+	fn limits() {
+		assert!(EmbedBuilder::new()
+			.title("a".repeat(256))
+			.field("b", "c".repeat(1024))
+			.build()
+			.is_ok());
+
+		let err = EmbedBuilder::new()
+			.title("a".repeat(257))
+			.build()
+			.unwrap_err();
+		assert!(matches!(
+			err,
+			Error::Validation(ValidationError::TooLong {
+				field: "embed title",
+				..
+			})
+		));
+
+		let embed = (0..6).fold(EmbedBuilder::new(), |e, _| e.field("a", "b".repeat(1024)));
+		assert!(matches!(
+			embed.build(),
+			Err(Error::Validation(ValidationError::TooLong {
+				field: "embed",
+				..
+			}))
+		));
+	}
+
+	#[test]
+	fn attachment_reference() {
+		let attachment = Attachment::new("chart.png", vec![]).spoiler();
+		let embed = EmbedBuilder::new().image_attachment(&attachment);
+		assert_eq!(
+			embed.embed.image.unwrap().url,
+			"attachment://SPOILER_chart.png"
+		);
+	}
+}
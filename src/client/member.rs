@@ -0,0 +1,284 @@
+use super::{body, Client, CowString, Error, ValidationError};
+use chrono::{DateTime, Utc};
+use discord_types::{ChannelId, GuildId, Member, RoleId, User, UserId};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Longest timeout Discord accepts
+const MAX_TIMEOUT: Duration = Duration::from_secs(28 * 24 * 3600);
+
+/// Maximum number of users in a single bulk ban
+const BULK_BAN_LIMIT: usize = 200;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Ban {
+	pub reason: Option<String>,
+	pub user: User,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct BulkBan {
+	pub banned_users: Vec<UserId>,
+	pub failed_users: Vec<UserId>,
+}
+
+impl Client {
+	pub fn modify_guild_member(&self, guild_id: GuildId, user_id: UserId) -> ModifyGuildMember<'_> {
+		ModifyGuildMember {
+			client: self,
+			guild_id,
+			user_id,
+			reason: None,
+			timeout: None,
+			mgm: Default::default(),
+		}
+	}
+
+	/// Kick a member from the guild
+	pub fn remove_guild_member(&self, guild_id: GuildId, user_id: UserId) -> RemoveGuildMember<'_> {
+		RemoveGuildMember {
+			client: self,
+			guild_id,
+			user_id,
+			reason: None,
+		}
+	}
+
+	pub async fn get_guild_bans(
+		&self,
+		guild_id: GuildId,
+		after: Option<UserId>,
+		limit: Option<u16>,
+	) -> Result<Vec<Ban>, Error> {
+		let limit = limit.unwrap_or(1000).min(1000);
+		let param = match after {
+			Some(a) => format!("&after={}", a),
+			None => String::new(),
+		};
+		self.get(&format!(
+			"guilds/{}/bans?limit={}{}",
+			guild_id, limit, param
+		))
+		.await
+	}
+
+	pub fn remove_guild_ban(&self, guild_id: GuildId, user_id: UserId) -> RemoveGuildBan<'_> {
+		RemoveGuildBan {
+			client: self,
+			guild_id,
+			user_id,
+			reason: None,
+		}
+	}
+
+	/// Ban multiple users at once.
+	/// Discord allows up to 200 users per request, larger lists are split into multiple requests
+	pub fn bulk_guild_ban<I>(&self, guild_id: GuildId, user_ids: I) -> BulkGuildBan<'_>
+	where
+		I: IntoIterator<Item = UserId>,
+	{
+		BulkGuildBan {
+			client: self,
+			guild_id,
+			user_ids: user_ids.into_iter().collect(),
+			delete_message_seconds: None,
+			reason: None,
+		}
+	}
+}
+
+pub struct ModifyGuildMember<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	user_id: UserId,
+	reason: Option<&'a str>,
+	/// Applied when sending, so the end of the timeout is relative to the request
+	timeout: Option<Duration>,
+	mgm: body::ModifyGuildMember,
+}
+
+impl<'a> ModifyGuildMember<'a> {
+	pub fn nick<T: Into<CowString>>(mut self, nick: T) -> Self {
+		self.mgm.nick = Some(Some(nick.into()));
+		self
+	}
+
+	pub fn clear_nick(mut self) -> Self {
+		self.mgm.nick = Some(None);
+		self
+	}
+
+	/// Replace all roles of the member
+	pub fn roles<I: IntoIterator<Item = RoleId>>(mut self, roles: I) -> Self {
+		self.mgm.roles = Some(roles.into_iter().collect());
+		self
+	}
+
+	pub fn mute(mut self, mute: bool) -> Self {
+		self.mgm.mute = Some(mute);
+		self
+	}
+
+	pub fn deaf(mut self, deaf: bool) -> Self {
+		self.mgm.deaf = Some(deaf);
+		self
+	}
+
+	/// Move the member to another voice channel
+	pub fn move_to(mut self, channel_id: ChannelId) -> Self {
+		self.mgm.channel_id = Some(Some(channel_id));
+		self
+	}
+
+	/// Disconnect the member from voice
+	pub fn disconnect(mut self) -> Self {
+		self.mgm.channel_id = Some(None);
+		self
+	}
+
+	/// Time out the member until `until`, at most 28 days in the future
+	pub fn timeout_until(mut self, until: DateTime<Utc>) -> Self {
+		self.timeout = None;
+		self.mgm.communication_disabled_until = Some(Some(until.to_rfc3339()));
+		self
+	}
+
+	/// Time out the member for `duration`, sending fails if it is longer than 28 days
+	pub fn timeout(mut self, duration: Duration) -> Self {
+		self.timeout = Some(duration);
+		self
+	}
+
+	pub fn clear_timeout(mut self) -> Self {
+		self.timeout = None;
+		self.mgm.communication_disabled_until = Some(None);
+		self
+	}
+
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(mut self) -> Result<Member, Error> {
+		if let Some(duration) = self.timeout {
+			let until = timeout_end(Utc::now(), duration)?;
+			self.mgm.communication_disabled_until = Some(Some(until.to_rfc3339()));
+		}
+		self.client
+			.patch(
+				&format!("guilds/{}/members/{}", self.guild_id, self.user_id),
+				self.mgm,
+				self.reason,
+			)
+			.await
+	}
+}
+
+pub struct RemoveGuildMember<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	user_id: UserId,
+	reason: Option<&'a str>,
+}
+
+impl<'a> RemoveGuildMember<'a> {
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<(), Error> {
+		self.client
+			.delete(
+				&format!("guilds/{}/members/{}", self.guild_id, self.user_id),
+				self.reason,
+			)
+			.await
+	}
+}
+
+pub struct RemoveGuildBan<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	user_id: UserId,
+	reason: Option<&'a str>,
+}
+
+impl<'a> RemoveGuildBan<'a> {
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<(), Error> {
+		self.client
+			.delete(
+				&format!("guilds/{}/bans/{}", self.guild_id, self.user_id),
+				self.reason,
+			)
+			.await
+	}
+}
+
+pub struct BulkGuildBan<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	user_ids: Vec<UserId>,
+	delete_message_seconds: Option<u32>,
+	reason: Option<&'a str>,
+}
+
+impl<'a> BulkGuildBan<'a> {
+	/// Delete messages of the banned users sent in the last `seconds`, at most 7 days
+	pub fn delete_message_seconds(mut self, seconds: u32) -> Self {
+		self.delete_message_seconds = Some(seconds);
+		self
+	}
+
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<BulkBan, Error> {
+		let url = format!("guilds/{}/bulk-ban", self.guild_id);
+		let mut result = BulkBan::default();
+		for user_ids in self.user_ids.chunks(BULK_BAN_LIMIT) {
+			let body = body::BulkGuildBan {
+				user_ids,
+				delete_message_seconds: self.delete_message_seconds,
+			};
+			let res: BulkBan = self.client.post(&url, body, self.reason).await?;
+			result.banned_users.extend(res.banned_users);
+			result.failed_users.extend(res.failed_users);
+		}
+		Ok(result)
+	}
+}
+
+fn timeout_end(now: DateTime<Utc>, duration: Duration) -> Result<DateTime<Utc>, ValidationError> {
+	let invalid = || ValidationError::Invalid("Timeouts can be at most 28 days".into());
+	if duration > MAX_TIMEOUT {
+		return Err(invalid());
+	}
+	let duration = chrono::Duration::from_std(duration).map_err(|_| invalid())?;
+	now.checked_add_signed(duration).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn timeout_length() {
+		let now = Utc::now();
+		assert_eq!(
+			timeout_end(now, Duration::from_secs(60)),
+			Ok(now + chrono::Duration::seconds(60))
+		);
+		assert!(timeout_end(now, MAX_TIMEOUT).is_ok());
+		assert!(timeout_end(now, MAX_TIMEOUT + Duration::from_secs(1)).is_err());
+		assert!(timeout_end(now, Duration::MAX).is_err());
+	}
+}
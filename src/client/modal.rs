@@ -0,0 +1,299 @@
+use super::{validate, Client, CowString, Error, ValidationError};
+use discord_types::InteractionId;
+use serde::Serialize;
+use serde_repr::Serialize_repr;
+use std::collections::HashSet;
+
+const INTERACTION_MODAL: u8 = 9;
+const COMPONENT_ACTION_ROW: u8 = 1;
+const COMPONENT_TEXT_INPUT: u8 = 4;
+const MAX_TEXT_INPUTS: usize = 5;
+const MAX_TEXT_INPUT_VALUE: usize = 4000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize_repr)]
+#[repr(u8)]
+pub enum TextInputStyle {
+	/// Single line
+	Short = 1,
+	/// Multiple lines
+	Paragraph = 2,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TextInputComponent {
+	#[serde(rename = "type")]
+	component_type: u8,
+	custom_id: CowString,
+	style: TextInputStyle,
+	label: CowString,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	min_length: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	max_length: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	required: Option<bool>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	value: Option<CowString>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	placeholder: Option<CowString>,
+}
+
+impl TextInputComponent {
+	fn new<T: Into<CowString>, U: Into<CowString>>(
+		style: TextInputStyle,
+		custom_id: T,
+		label: U,
+	) -> Self {
+		Self {
+			component_type: COMPONENT_TEXT_INPUT,
+			custom_id: custom_id.into(),
+			style,
+			label: label.into(),
+			min_length: None,
+			max_length: None,
+			required: None,
+			value: None,
+			placeholder: None,
+		}
+	}
+
+	/// Label of up to 45 characters
+	pub fn short<T: Into<CowString>, U: Into<CowString>>(custom_id: T, label: U) -> Self {
+		Self::new(TextInputStyle::Short, custom_id, label)
+	}
+
+	pub fn paragraph<T: Into<CowString>, U: Into<CowString>>(custom_id: T, label: U) -> Self {
+		Self::new(TextInputStyle::Paragraph, custom_id, label)
+	}
+
+	/// Up to 4000
+	pub fn min_length(mut self, min_length: u16) -> Self {
+		self.min_length = Some(min_length);
+		self
+	}
+
+	/// Up to 4000
+	pub fn max_length(mut self, max_length: u16) -> Self {
+		self.max_length = Some(max_length);
+		self
+	}
+
+	/// Text inputs are required by default
+	pub fn required(mut self, required: bool) -> Self {
+		self.required = Some(required);
+		self
+	}
+
+	/// Pre-filled value
+	pub fn value<T: Into<CowString>>(mut self, value: T) -> Self {
+		self.value = Some(value.into());
+		self
+	}
+
+	/// Up to 100 characters
+	pub fn placeholder<T: Into<CowString>>(mut self, placeholder: T) -> Self {
+		self.placeholder = Some(placeholder.into());
+		self
+	}
+
+	fn validate(&self) -> Result<(), Error> {
+		validate::len("text input custom id", &self.custom_id, 100)?;
+		validate::len("text input label", &self.label, 45)?;
+		if let Some(placeholder) = &self.placeholder {
+			validate::len("text input placeholder", placeholder, 100)?;
+		}
+		if let Some(value) = &self.value {
+			validate::len("text input value", value, MAX_TEXT_INPUT_VALUE)?;
+		}
+		for length in [self.min_length, self.max_length].into_iter().flatten() {
+			if length as usize > MAX_TEXT_INPUT_VALUE {
+				return Err(ValidationError::TooLong {
+					field: "text input length",
+					len: length as usize,
+					max: MAX_TEXT_INPUT_VALUE,
+				}
+				.into());
+			}
+		}
+		if let (Some(min), Some(max)) = (self.min_length, self.max_length) {
+			if min > max {
+				return Err(ValidationError::Invalid(format!(
+					"Minimum length of text input '{}' is above its maximum",
+					self.custom_id
+				))
+				.into());
+			}
+		}
+		Ok(())
+	}
+}
+
+#[derive(Serialize)]
+struct ActionRow<'a> {
+	#[serde(rename = "type")]
+	component_type: u8,
+	components: [&'a TextInputComponent; 1],
+}
+
+/// Dialog with up to 5 text inputs, shown in response to a command or component interaction
+#[derive(Clone, Debug)]
+pub struct ModalBuilder {
+	custom_id: CowString,
+	title: CowString,
+	inputs: Vec<TextInputComponent>,
+}
+
+impl ModalBuilder {
+	/// Title of up to 45 characters
+	pub fn new<T: Into<CowString>, U: Into<CowString>>(custom_id: T, title: U) -> Self {
+		Self {
+			custom_id: custom_id.into(),
+			title: title.into(),
+			inputs: Vec::new(),
+		}
+	}
+
+	/// Each text input is placed in its own row
+	pub fn text_input(mut self, input: TextInputComponent) -> Self {
+		self.inputs.push(input);
+		self
+	}
+
+	pub fn validate(&self) -> Result<(), Error> {
+		validate::len("modal custom id", &self.custom_id, 100)?;
+		validate::len("modal title", &self.title, 45)?;
+		validate::count("text inputs", self.inputs.len(), MAX_TEXT_INPUTS)?;
+		if self.inputs.is_empty() {
+			return Err(
+				ValidationError::Invalid("Modals need at least one text input".into()).into(),
+			);
+		}
+		let mut ids = HashSet::new();
+		for input in &self.inputs {
+			input.validate()?;
+			if !ids.insert(&input.custom_id) {
+				return Err(ValidationError::Invalid(format!(
+					"Duplicate text input custom id '{}'",
+					input.custom_id
+				))
+				.into());
+			}
+		}
+		Ok(())
+	}
+
+	fn body(&self) -> ModalResponse<'_> {
+		ModalResponse {
+			response_type: INTERACTION_MODAL,
+			data: ModalData {
+				custom_id: &self.custom_id,
+				title: &self.title,
+				components: self
+					.inputs
+					.iter()
+					.map(|input| ActionRow {
+						component_type: COMPONENT_ACTION_ROW,
+						components: [input],
+					})
+					.collect(),
+			},
+		}
+	}
+}
+
+#[derive(Serialize)]
+struct ModalResponse<'a> {
+	#[serde(rename = "type")]
+	response_type: u8,
+	data: ModalData<'a>,
+}
+
+#[derive(Serialize)]
+struct ModalData<'a> {
+	custom_id: &'a str,
+	title: &'a str,
+	components: Vec<ActionRow<'a>>,
+}
+
+impl Client {
+	/// Show a modal, not possible in response to a modal submit
+	pub async fn modal_response(
+		&self,
+		interaction_id: InteractionId,
+		token: &str,
+		modal: &ModalBuilder,
+	) -> Result<(), Error> {
+		modal.validate()?;
+		self.post_discard(
+			&format!("interactions/{}/{}/callback", interaction_id, token),
+			modal.body(),
+			None,
+		)
+		.await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn modal_body() {
+		let modal = ModalBuilder::new("report:1234", "Report message").text_input(
+			TextInputComponent::paragraph("reason", "Reason")
+				.max_length(1000)
+				.required(false),
+		);
+		assert!(modal.validate().is_ok());
+		assert_eq!(
+			serde_json::to_value(modal.body()).unwrap(),
+			serde_json::json!({
+				"type": 9,
+				"data": {
+					"custom_id": "report:1234",
+					"title": "Report message",
+					"components": [{
+						"type": 1,
+						"components": [{
+							"type": 4,
+							"custom_id": "reason",
+							"style": 2,
+							"label": "Reason",
+							"max_length": 1000,
+							"required": false,
+						}],
+					}],
+				},
+			})
+		);
+
+		let modal = (0..6).fold(ModalBuilder::new("a", "b"), |m, i| {
+			m.text_input(TextInputComponent::short(i.to_string(), "c"))
+		});
+		assert!(modal.validate().is_err());
+	}
+
+	#[test]
+	fn invalid_modals() {
+		assert!(ModalBuilder::new("a", "b").validate().is_err());
+
+		let modal = ModalBuilder::new("a", "b").text_input(
+			TextInputComponent::short("c", "d")
+				.min_length(10)
+				.max_length(5),
+		);
+		assert!(modal.validate().is_err());
+
+		let modal = ModalBuilder::new("a", "b")
+			.text_input(TextInputComponent::short("c", "d"))
+			.text_input(TextInputComponent::paragraph("c", "e"));
+		assert!(modal.validate().is_err());
+
+		let modal = ModalBuilder::new("a", "b").text_input(
+			TextInputComponent::short("c", "d")
+				.min_length(5)
+				.max_length(5),
+		);
+		assert!(modal.validate().is_ok());
+	}
+}
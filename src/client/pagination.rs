@@ -0,0 +1,168 @@
+use super::{Ban, Client, CurrentUserGuild, Error, DISCORD_EPOCH};
+use discord_types::{ChannelId, GuildId, Member, Message, MessageId, PartialEmoji, User};
+use futures::stream::{self, Stream, TryStreamExt};
+use std::fmt;
+use std::future::Future;
+
+/// Position in the channel history to fetch messages relative to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageAnchor {
+	Before(MessageId),
+	After(MessageId),
+	Around(MessageId),
+}
+
+impl MessageAnchor {
+	pub(crate) fn query(&self) -> String {
+		match self {
+			MessageAnchor::Before(id) => format!("&before={}", id),
+			MessageAnchor::After(id) => format!("&after={}", id),
+			MessageAnchor::Around(id) => format!("&around={}", id),
+		}
+	}
+}
+
+impl Client {
+	/// All members of the guild, in order of their user id
+	pub fn guild_members_stream(
+		&self,
+		guild_id: GuildId,
+	) -> impl Stream<Item = Result<Member, Error>> + '_ {
+		paginate(None, move |after| async move {
+			let page = self.get_guild_members(guild_id, after, None).await?;
+			let next = if page.len() < 1000 {
+				None
+			} else {
+				page.last().and_then(|m| m.user.as_ref()).map(|u| u.id)
+			};
+			Ok((page, next))
+		})
+	}
+
+	/// All bans of the guild, in order of the banned user's id
+	pub fn guild_bans_stream(
+		&self,
+		guild_id: GuildId,
+	) -> impl Stream<Item = Result<Ban, Error>> + '_ {
+		paginate(None, move |after| async move {
+			let page = self.get_guild_bans(guild_id, after, None).await?;
+			let next = if page.len() < 1000 {
+				None
+			} else {
+				page.last().map(|b| b.user.id)
+			};
+			Ok((page, next))
+		})
+	}
+
+	/// All guilds the current user is in, in order of their id
+	pub fn current_user_guilds_stream(
+		&self,
+	) -> impl Stream<Item = Result<CurrentUserGuild, Error>> + '_ {
+		paginate(None, move |after| async move {
+			let page = self.get_current_user_guilds(after, None).await?;
+			let next = if page.len() < 200 {
+				None
+			} else {
+				page.last().map(|g| g.id)
+			};
+			Ok((page, next))
+		})
+	}
+
+	/// All users that reacted with `emoji`
+	pub fn reactions_stream(
+		&self,
+		channel_id: ChannelId,
+		message_id: MessageId,
+		emoji: &PartialEmoji,
+	) -> impl Stream<Item = Result<User, Error>> + '_ {
+		let emoji = emoji.clone();
+		paginate(None, move |after| {
+			let emoji = emoji.clone();
+			async move {
+				let page = self
+					.get_reactions(channel_id, message_id, &emoji, after, None)
+					.await?;
+				let next = if page.len() < 100 {
+					None
+				} else {
+					page.last().map(|u| u.id)
+				};
+				Ok((page, next))
+			}
+		})
+	}
+
+	/// Walk the channel history starting at `anchor`, or at the latest message if `None`.
+	/// `Before` walks back to the start of the channel, `After` walks forward to the latest
+	/// message. `Around` only yields the messages of a single page
+	pub fn channel_messages_stream(
+		&self,
+		channel_id: ChannelId,
+		anchor: Option<MessageAnchor>,
+	) -> impl Stream<Item = Result<Message, Error>> + '_ {
+		paginate(anchor, move |anchor| async move {
+			let page = self.get_channel_messages(channel_id, anchor, None).await?;
+			let next = match anchor {
+				_ if page.len() < 100 => None,
+				None | Some(MessageAnchor::Before(_)) => page
+					.iter()
+					.min_by_key(|m| snowflake(m.id))
+					.map(|m| MessageAnchor::Before(m.id)),
+				Some(MessageAnchor::After(_)) => page
+					.iter()
+					.max_by_key(|m| snowflake(m.id))
+					.map(|m| MessageAnchor::After(m.id)),
+				Some(MessageAnchor::Around(_)) => None,
+			};
+			Ok((page, next))
+		})
+	}
+}
+
+/// Flatten pages into a single stream.
+/// `fetch` receives the cursor of the page and returns its items along with the cursor
+/// of the next page, or `None` if it was the last one
+pub(crate) fn paginate<T, C, F, Fut>(
+	start: Option<C>,
+	fetch: F,
+) -> impl Stream<Item = Result<T, Error>>
+where
+	F: FnMut(Option<C>) -> Fut,
+	Fut: Future<Output = Result<(Vec<T>, Option<C>), Error>>,
+{
+	stream::try_unfold(Some((start, fetch)), |state| async move {
+		let (cursor, mut fetch) = match state {
+			Some(s) => s,
+			None => return Ok::<_, Error>(None),
+		};
+		let (page, next) = fetch(cursor).await?;
+		let state = match next {
+			Some(next) if !page.is_empty() => Some((Some(next), fetch)),
+			_ => None,
+		};
+		Ok(Some((stream::iter(page.into_iter().map(Ok)), state)))
+	})
+	.try_flatten()
+}
+
+/// Numeric value of a snowflake id
+pub(crate) fn snowflake<T: fmt::Display>(id: T) -> u64 {
+	id.to_string().parse().unwrap_or(0)
+}
+
+/// Creation time of a snowflake id, in milliseconds since the Unix epoch
+pub(crate) fn snowflake_timestamp<T: fmt::Display>(id: T) -> u64 {
+	(snowflake(id) >> 22) + DISCORD_EPOCH
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn snowflake_time() {
+		assert_eq!(snowflake_timestamp(175928847299117063u64), 1462015105796);
+	}
+}
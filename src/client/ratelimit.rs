@@ -0,0 +1,294 @@
+use log::{debug, warn};
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Response, StatusCode};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use tokio::time::{self, Instant};
+
+/// Maximum number of requests per second across all routes
+const GLOBAL_LIMIT: u32 = 50;
+const GLOBAL_WINDOW: Duration = Duration::from_secs(1);
+
+/// Identifies the rate limit of a single REST route.
+/// Ids are stripped from the path, except for the top-level
+/// channel, guild or webhook id, which Discord treats as a major parameter.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Route {
+	key: String,
+	major: String,
+}
+
+impl Route {
+	pub(crate) fn new(method: &Method, path: &str) -> Self {
+		let path = path.split('?').next().unwrap_or(path);
+		let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+		let root = segments.first().copied().unwrap_or("");
+
+		let mut major = String::new();
+		let mut key = method.as_str().to_owned();
+		key.push(' ');
+		let mut in_reactions = false;
+		for (i, &segment) in segments.iter().enumerate() {
+			if i > 0 {
+				key.push('/');
+			}
+			if i == 1 && matches!(root, "channels" | "guilds" | "webhooks") {
+				major = format!("{}/{}", root, segment);
+				key.push_str(segment);
+			} else if i == 2 && root == "webhooks" {
+				// Webhook token, part of the major parameter
+				major.push('/');
+				major.push_str(segment);
+				key.push_str(":token");
+			} else if i == 2 && root == "interactions" {
+				key.push_str(":token");
+			} else if in_reactions {
+				// Emoji and user ids all share the same bucket
+				key.push_str(":reaction");
+			} else if segment.bytes().all(|b| b.is_ascii_digit()) {
+				key.push_str(":id");
+			} else {
+				key.push_str(segment);
+			}
+			in_reactions |= segment == "reactions";
+		}
+
+		Self { key, major }
+	}
+
+	fn is_global_exempt(&self) -> bool {
+		self.key.contains(" interactions/")
+	}
+}
+
+/// Method and path with ids and tokens masked, safe to log
+impl fmt::Display for Route {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.key)
+	}
+}
+
+#[derive(Debug, Default)]
+struct Bucket {
+	remaining: Option<u32>,
+	reset: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Global {
+	blocked_until: Option<Instant>,
+	window: Instant,
+	count: u32,
+}
+
+/// Keeps track of Discord's per-route buckets and the global rate limit.
+/// Requests to the same bucket are queued: only a single request per bucket is in flight
+/// and a request waits until the bucket resets if it has no remaining capacity.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+	global: Mutex<Global>,
+	routes: Mutex<HashMap<String, String>>,
+	buckets: Mutex<HashMap<String, Arc<AsyncMutex<Bucket>>>>,
+}
+
+impl RateLimiter {
+	pub(crate) fn new() -> Self {
+		Self {
+			global: Mutex::new(Global {
+				blocked_until: None,
+				window: Instant::now(),
+				count: 0,
+			}),
+			routes: Mutex::new(HashMap::new()),
+			buckets: Mutex::new(HashMap::new()),
+		}
+	}
+
+	fn bucket_key(&self, route: &Route) -> String {
+		let routes = self.routes.lock().unwrap();
+		let hash = routes.get(&route.key).unwrap_or(&route.key);
+		format!("{}:{}", hash, route.major)
+	}
+
+	fn bucket(&self, key: String) -> Arc<AsyncMutex<Bucket>> {
+		self.buckets.lock().unwrap().entry(key).or_default().clone()
+	}
+
+	/// Wait until a request to `route` can be sent without hitting a rate limit.
+	/// The returned ticket holds the bucket until the response is passed to `Ticket::complete`
+	pub(crate) async fn acquire(self: &Arc<Self>, route: &Route) -> Ticket {
+		let bucket = self.bucket(self.bucket_key(route));
+		let mut state = bucket.clone().lock_owned().await;
+
+		if let (Some(0), Some(reset)) = (state.remaining, state.reset) {
+			if reset > Instant::now() {
+				debug!("Bucket for '{}' exhausted, waiting for reset", route.key);
+				time::sleep_until(reset).await;
+			}
+		}
+		if state.reset.map(|r| r <= Instant::now()).unwrap_or(false) {
+			state.remaining = None;
+			state.reset = None;
+		}
+
+		if !route.is_global_exempt() {
+			self.wait_global().await;
+		}
+
+		Ticket {
+			limiter: self.clone(),
+			route: route.clone(),
+			bucket,
+			state,
+		}
+	}
+
+	async fn wait_global(&self) {
+		loop {
+			let wait = {
+				let mut global = self.global.lock().unwrap();
+				let now = Instant::now();
+				match global.blocked_until {
+					Some(until) if until > now => Some(until),
+					_ => {
+						global.blocked_until = None;
+						if now >= global.window + GLOBAL_WINDOW {
+							global.window = now;
+							global.count = 0;
+						}
+						if global.count >= GLOBAL_LIMIT {
+							Some(global.window + GLOBAL_WINDOW)
+						} else {
+							global.count += 1;
+							None
+						}
+					}
+				}
+			};
+
+			match wait {
+				Some(until) => time::sleep_until(until).await,
+				None => return,
+			}
+		}
+	}
+
+	fn block_global(&self, duration: Duration) {
+		warn!("Hit global rate limit, blocking for {:?}", duration);
+		let until = Instant::now() + duration;
+		let mut global = self.global.lock().unwrap();
+		if global.blocked_until.map(|u| u < until).unwrap_or(true) {
+			global.blocked_until = Some(until);
+		}
+	}
+}
+
+pub(crate) struct Ticket {
+	limiter: Arc<RateLimiter>,
+	route: Route,
+	bucket: Arc<AsyncMutex<Bucket>>,
+	state: OwnedMutexGuard<Bucket>,
+}
+
+impl Ticket {
+	/// Update the bucket from the rate limit headers of the response and release it
+	pub(crate) fn complete(mut self, res: &Response) {
+		let headers = res.headers();
+		let now = Instant::now();
+
+		if let Some(hash) = header::<String>(headers, "x-ratelimit-bucket") {
+			let mut routes = self.limiter.routes.lock().unwrap();
+			if routes.get(&self.route.key) != Some(&hash) {
+				// Requests to this route will now be keyed by the bucket hash,
+				// which might be shared with other routes
+				let key = format!("{}:{}", hash, self.route.major);
+				routes.insert(self.route.key.clone(), hash);
+				self.limiter
+					.buckets
+					.lock()
+					.unwrap()
+					.entry(key)
+					.or_insert_with(|| self.bucket.clone());
+			}
+		}
+
+		if let Some(remaining) = header::<u32>(headers, "x-ratelimit-remaining") {
+			self.state.remaining = Some(remaining);
+		}
+		if let Some(reset_after) = header::<f64>(headers, "x-ratelimit-reset-after") {
+			self.state.reset = Some(now + Duration::from_secs_f64(reset_after.max(0.0)));
+		}
+
+		if res.status() == StatusCode::TOO_MANY_REQUESTS {
+			let retry_after = retry_after(headers).unwrap_or(Duration::from_secs(1));
+			if header::<bool>(headers, "x-ratelimit-global").unwrap_or(false) {
+				self.limiter.block_global(retry_after);
+			} else {
+				debug!("Rate limited on '{}' for {:?}", self.route.key, retry_after);
+				self.state.remaining = Some(0);
+				self.state.reset = Some(now + retry_after);
+			}
+		}
+	}
+}
+
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+	header::<f64>(headers, "retry-after").map(|s| Duration::from_secs_f64(s.max(0.0)))
+}
+
+fn header<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+	headers.get(name)?.to_str().ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn route_major() {
+		let a = Route::new(&Method::GET, "channels/1234/messages/5678");
+		let b = Route::new(&Method::GET, "channels/1234/messages/9012");
+		let c = Route::new(&Method::GET, "channels/4321/messages/5678");
+		assert_eq!(a, b);
+		assert_ne!(a, c);
+		assert_eq!(a.key, "GET channels/1234/messages/:id");
+		assert_eq!(a.major, "channels/1234");
+	}
+
+	#[test]
+	fn route_reactions() {
+		let a = Route::new(
+			&Method::PUT,
+			"channels/1/messages/2/reactions/%F0%9F%91%8D/@me",
+		);
+		let b = Route::new(
+			&Method::PUT,
+			"channels/1/messages/3/reactions/name%3A42/@me",
+		);
+		assert_eq!(a, b);
+		assert_eq!(
+			a.key,
+			"PUT channels/1/messages/:id/reactions/:reaction/:reaction"
+		);
+	}
+
+	#[test]
+	fn route_query_and_tokens() {
+		let a = Route::new(&Method::GET, "guilds/10/members?limit=1000&after=5");
+		assert_eq!(a.key, "GET guilds/10/members");
+		assert_eq!(a.major, "guilds/10");
+
+		let b = Route::new(&Method::POST, "interactions/123/abcdef/callback");
+		assert_eq!(b.key, "POST interactions/:id/:token/callback");
+		assert_eq!(b.to_string(), b.key);
+		assert!(b.is_global_exempt());
+
+		let c = Route::new(&Method::POST, "webhooks/123/abcdef");
+		assert_eq!(c.key, "POST webhooks/123/:token");
+		assert_eq!(c.major, "webhooks/123/abcdef");
+		assert!(!c.to_string().contains("abcdef"));
+	}
+}
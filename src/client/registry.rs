@@ -0,0 +1,349 @@
+use super::command::commands_url;
+use super::{Client, Error, NewCommand};
+use discord_types::{ApplicationId, GuildId};
+use log::info;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Fields of a command that are compared when looking for changes
+const COMPARED: [&str; 9] = [
+	"name",
+	"type",
+	"description",
+	"options",
+	"default_member_permissions",
+	"dm_permission",
+	"nsfw",
+	"name_localizations",
+	"description_localizations",
+];
+
+/// Commands declared in code, to be kept in sync with the ones registered in Discord
+#[derive(Clone, Debug, Default)]
+pub struct CommandRegistry {
+	commands: Vec<NewCommand>,
+}
+
+impl CommandRegistry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Replaces an earlier command with the same name and type
+	pub fn command(mut self, command: NewCommand) -> Self {
+		self.commands
+			.retain(|c| c.name() != command.name() || c.command_type() != command.command_type());
+		self.commands.push(command);
+		self
+	}
+
+	pub fn commands(&self) -> impl Iterator<Item = &NewCommand> {
+		self.commands.iter()
+	}
+
+	/// Changes needed to go from the `existing` commands, as returned by Discord, to the
+	/// declared ones. Raw JSON is compared so no field is lost to deserialization
+	fn diff(&self, existing: Vec<Value>) -> Result<Vec<CommandChange>, Error> {
+		let mut existing: Vec<_> = existing
+			.into_iter()
+			.map(|c| (command_id(&c), normalize(c)))
+			.collect();
+		let mut changes = Vec::new();
+
+		for command in &self.commands {
+			let new = normalize(serde_json::to_value(command)?);
+			let index = existing
+				.iter()
+				.position(|(_, e)| e["name"] == new["name"] && e["type"] == new["type"]);
+			match index.map(|i| existing.swap_remove(i)) {
+				Some((id, old)) if old != new => changes.push(CommandChange::Edit {
+					id,
+					command: command.clone(),
+				}),
+				Some(_) => {}
+				None => changes.push(CommandChange::Create(command.clone())),
+			}
+		}
+
+		changes.extend(existing.into_iter().map(|(id, old)| CommandChange::Delete {
+			id,
+			name: old["name"].as_str().unwrap_or_default().to_owned(),
+		}));
+		Ok(changes)
+	}
+}
+
+/// Change to the registered commands, `Display` describes it for dry runs
+#[derive(Clone, Debug)]
+pub enum CommandChange {
+	Create(NewCommand),
+	Edit { id: String, command: NewCommand },
+	Delete { id: String, name: String },
+}
+
+impl fmt::Display for CommandChange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			CommandChange::Create(command) => write!(f, "Create command '{}'", command.name()),
+			CommandChange::Edit { id, command } => {
+				write!(f, "Edit command '{}' ({})", command.name(), id)
+			}
+			CommandChange::Delete { id, name } => write!(f, "Delete command '{}' ({})", name, id),
+		}
+	}
+}
+
+fn command_id(command: &Value) -> String {
+	match &command["id"] {
+		Value::String(id) => id.clone(),
+		id => id.to_string(),
+	}
+}
+
+/// Only keep the fields we declare, without the defaults Discord fills in
+fn normalize(command: Value) -> Value {
+	let mut map = Map::new();
+	if let Value::Object(fields) = command {
+		for (key, value) in fields {
+			if COMPARED.contains(&key.as_str()) {
+				if let Some(value) = clean(value) {
+					map.insert(key, value);
+				}
+			}
+		}
+	}
+
+	map.entry("type").or_insert_with(|| 1.into());
+	if map.get("dm_permission") == Some(&Value::Bool(true)) {
+		map.remove("dm_permission");
+	}
+	if map.get("nsfw") == Some(&Value::Bool(false)) {
+		map.remove("nsfw");
+	}
+	if map.get("description").and_then(Value::as_str) == Some("") {
+		map.remove("description");
+	}
+	// Permissions are sent as a string, but may be serialized as a number
+	if let Some(Value::Number(n)) = map.get("default_member_permissions") {
+		let permissions = n.to_string();
+		map.insert("default_member_permissions".into(), permissions.into());
+	}
+	Value::Object(map)
+}
+
+/// Drop nulls, empty arrays and objects, and `required: false` of options
+fn clean(value: Value) -> Option<Value> {
+	match value {
+		Value::Null => None,
+		Value::Array(values) => {
+			let values: Vec<_> = values.into_iter().filter_map(clean).collect();
+			(!values.is_empty()).then_some(Value::Array(values))
+		}
+		Value::Object(fields) => {
+			let fields: Map<_, _> = fields
+				.into_iter()
+				.filter(|(k, v)| k != "required" || v != &Value::Bool(false))
+				.filter_map(|(k, v)| Some((k, clean(v)?)))
+				.collect();
+			(!fields.is_empty()).then_some(Value::Object(fields))
+		}
+		value => Some(value),
+	}
+}
+
+impl Client {
+	pub fn sync_global_commands<'a>(
+		&'a self,
+		application_id: ApplicationId,
+		registry: &'a CommandRegistry,
+	) -> SyncCommands<'a> {
+		SyncCommands::new(self, application_id, None, registry)
+	}
+
+	pub fn sync_guild_commands<'a>(
+		&'a self,
+		application_id: ApplicationId,
+		guild_id: GuildId,
+		registry: &'a CommandRegistry,
+	) -> SyncCommands<'a> {
+		SyncCommands::new(self, application_id, Some(guild_id), registry)
+	}
+}
+
+/// Create, edit and delete commands so the registered ones match the registry.
+/// Returns the changes that were made, or would be made for a dry run
+pub struct SyncCommands<'a> {
+	client: &'a Client,
+	application_id: ApplicationId,
+	guild_id: Option<GuildId>,
+	registry: &'a CommandRegistry,
+	dry_run: bool,
+	bulk: bool,
+}
+
+impl<'a> SyncCommands<'a> {
+	fn new(
+		client: &'a Client,
+		application_id: ApplicationId,
+		guild_id: Option<GuildId>,
+		registry: &'a CommandRegistry,
+	) -> Self {
+		Self {
+			client,
+			application_id,
+			guild_id,
+			registry,
+			dry_run: false,
+			bulk: false,
+		}
+	}
+
+	/// Only report the planned changes
+	pub fn dry_run(mut self) -> Self {
+		self.dry_run = true;
+		self
+	}
+
+	/// Apply the changes with a single bulk overwrite instead of a request per command
+	pub fn bulk(mut self) -> Self {
+		self.bulk = true;
+		self
+	}
+
+	pub async fn send(self) -> Result<Vec<CommandChange>, Error> {
+		let client = self.client;
+		let application_id = self.application_id;
+		let existing = client
+			.get(&commands_url(application_id, self.guild_id))
+			.await?;
+
+		let changes = self.registry.diff(existing)?;
+		if self.dry_run || changes.is_empty() {
+			return Ok(changes);
+		}
+
+		if self.bulk {
+			let commands = &self.registry.commands;
+			match self.guild_id {
+				Some(guild_id) => {
+					client
+						.bulk_overwrite_guild_commands(application_id, guild_id, commands)
+						.await?
+				}
+				None => {
+					client
+						.bulk_overwrite_global_commands(application_id, commands)
+						.await?
+				}
+			};
+			info!("Overwrote commands ({} changes)", changes.len());
+			return Ok(changes);
+		}
+
+		for change in &changes {
+			match (change, self.guild_id) {
+				(CommandChange::Create(command), Some(guild_id)) => {
+					client
+						.create_guild_command(application_id, guild_id, command)
+						.await?;
+				}
+				(CommandChange::Create(command), None) => {
+					client
+						.create_global_command(application_id, command)
+						.await?;
+				}
+				(CommandChange::Edit { id, command }, Some(guild_id)) => {
+					client
+						.edit_guild_command(application_id, guild_id, id, command)
+						.await?;
+				}
+				(CommandChange::Edit { id, command }, None) => {
+					client
+						.edit_global_command(application_id, id, command)
+						.await?;
+				}
+				(CommandChange::Delete { id, .. }, Some(guild_id)) => {
+					client
+						.delete_guild_command(application_id, guild_id, id)
+						.await?;
+				}
+				(CommandChange::Delete { id, .. }, None) => {
+					client.delete_global_command(application_id, id).await?;
+				}
+			}
+			info!("{}", change);
+		}
+		Ok(changes)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use discord_types::Permissions;
+	use serde_json::json;
+
+	#[test]
+	fn plan_changes() {
+		let registry = CommandRegistry::new()
+			.command(NewCommand::chat_input("ping", "Ping the bot"))
+			.command(NewCommand::chat_input("roll", "Roll a die").nsfw(true))
+			.command(NewCommand::message("Quote"));
+		let existing = vec![
+			json!({
+				"id": "1",
+				"type": 1,
+				"name": "ping",
+				"description": "Ping the bot",
+				"dm_permission": true,
+				"nsfw": false,
+				"default_member_permissions": null,
+			}),
+			json!({"id": "2", "type": 1, "name": "roll", "description": "Roll a die"}),
+			json!({"id": "3", "type": 1, "name": "old", "description": "Removed"}),
+		];
+
+		let changes: Vec<_> = registry
+			.diff(existing)
+			.unwrap()
+			.iter()
+			.map(ToString::to_string)
+			.collect();
+		assert_eq!(
+			changes,
+			[
+				"Edit command 'roll' (2)",
+				"Create command 'Quote'",
+				"Delete command 'old' (3)",
+			]
+		);
+	}
+
+	#[test]
+	fn unchanged_commands() {
+		let registry = CommandRegistry::new()
+			.command(
+				NewCommand::chat_input("ban", "Ban a member")
+					.default_member_permissions(Permissions::empty())
+					.nsfw(true),
+			)
+			.command(NewCommand::user("ban"));
+		let existing = vec![
+			json!({"id": "1", "type": 2, "name": "ban", "description": ""}),
+			json!({
+				"id": "2",
+				"type": 1,
+				"name": "ban",
+				"description": "Ban a member",
+				"default_member_permissions": "0",
+				"nsfw": true,
+			}),
+		];
+		assert!(registry.diff(existing.clone()).unwrap().is_empty());
+
+		let registry = registry.command(NewCommand::chat_input("ban", "Ban a member"));
+		let changes = registry.diff(existing).unwrap();
+		assert_eq!(changes.len(), 1);
+		assert_eq!(changes[0].to_string(), "Edit command 'ban' (2)");
+	}
+}
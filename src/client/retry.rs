@@ -0,0 +1,158 @@
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Controls how `Client` retries requests that failed because of a rate limit
+/// or a transient server error.
+///
+/// Rate limited requests are always safe to retry and wait for the `retry_after` duration
+/// returned by Discord. Server errors (500, 502, 503 and 504) are only retried for idempotent
+/// methods, using exponential backoff with full jitter.
+///
+/// Requests that upload files are never retried, not even when rate limited, since their
+/// multipart body is consumed by the first attempt. Their error is returned instead.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+	max_retries: u32,
+	base_delay: Duration,
+	max_delay: Duration,
+	max_retry_after: Duration,
+}
+
+impl RetryPolicy {
+	pub fn new() -> Self {
+		Self {
+			max_retries: 3,
+			base_delay: Duration::from_millis(500),
+			max_delay: Duration::from_secs(10),
+			max_retry_after: Duration::from_secs(60),
+		}
+	}
+
+	/// Never retry, the error of the first attempt is returned
+	pub fn disabled() -> Self {
+		Self::new().max_retries(0)
+	}
+
+	pub fn max_retries(mut self, max_retries: u32) -> Self {
+		self.max_retries = max_retries;
+		self
+	}
+
+	/// Initial delay before retrying a server error, doubled on every attempt
+	pub fn base_delay(mut self, base_delay: Duration) -> Self {
+		self.base_delay = base_delay;
+		self
+	}
+
+	/// Upper bound of the backoff delay for server errors
+	pub fn max_delay(mut self, max_delay: Duration) -> Self {
+		self.max_delay = max_delay;
+		self
+	}
+
+	/// Rate limits with a longer `retry_after` are returned as an error instead of waited on
+	pub fn max_retry_after(mut self, max_retry_after: Duration) -> Self {
+		self.max_retry_after = max_retry_after;
+		self
+	}
+
+	/// Delay before the next attempt, if the request should be retried at all.
+	/// `attempt` is the number of attempts made so far
+	pub(crate) fn delay(
+		&self,
+		method: &Method,
+		status: StatusCode,
+		retry_after: Option<Duration>,
+		attempt: u32,
+	) -> Option<Duration> {
+		if attempt > self.max_retries {
+			return None;
+		}
+
+		match status.as_u16() {
+			429 => {
+				let retry_after = retry_after.unwrap_or(self.base_delay);
+				if retry_after <= self.max_retry_after {
+					Some(retry_after)
+				} else {
+					None
+				}
+			}
+			500 | 502 | 503 | 504 if is_idempotent(method) => Some(self.backoff(attempt)),
+			_ => None,
+		}
+	}
+
+	fn backoff(&self, attempt: u32) -> Duration {
+		let exp = self
+			.base_delay
+			.saturating_mul(1 << (attempt - 1).min(16))
+			.min(self.max_delay);
+		exp.mul_f64(jitter())
+	}
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Body of a 429 response
+#[derive(Deserialize)]
+pub(crate) struct RateLimited {
+	retry_after: f64,
+}
+
+impl RateLimited {
+	pub(crate) fn retry_after(&self) -> Duration {
+		Duration::from_secs_f64(self.retry_after.max(0.0))
+	}
+}
+
+fn is_idempotent(method: &Method) -> bool {
+	matches!(
+		*method,
+		Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+	)
+}
+
+/// Random factor in `[0, 1)`
+fn jitter() -> f64 {
+	let random = RandomState::new().build_hasher().finish();
+	(random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn retry_status() {
+		let policy = RetryPolicy::new();
+		let status = |s| StatusCode::from_u16(s).unwrap();
+		let second = Some(Duration::from_secs(1));
+
+		assert_eq!(policy.delay(&Method::POST, status(429), second, 1), second);
+		assert!(policy.delay(&Method::GET, status(502), None, 1).is_some());
+		assert!(policy.delay(&Method::POST, status(502), None, 1).is_none());
+		assert!(policy.delay(&Method::GET, status(404), None, 1).is_none());
+		assert!(policy.delay(&Method::GET, status(502), None, 4).is_none());
+		assert!(RetryPolicy::disabled()
+			.delay(&Method::GET, status(429), second, 1)
+			.is_none());
+	}
+
+	#[test]
+	fn backoff_bounds() {
+		let policy = RetryPolicy::new()
+			.base_delay(Duration::from_millis(100))
+			.max_delay(Duration::from_millis(300));
+		for attempt in 1..10 {
+			assert!(policy.backoff(attempt) <= Duration::from_millis(300));
+		}
+	}
+}
@@ -0,0 +1,178 @@
+use super::{body, AuditRequest, Client, CowString, Error};
+use discord_types::{GuildId, Permissions, Role, RoleId};
+use reqwest::Method;
+
+impl Client {
+	pub fn create_guild_role(&self, guild_id: GuildId) -> CreateGuildRole<'_> {
+		CreateGuildRole {
+			client: self,
+			guild_id,
+			reason: None,
+			role: Default::default(),
+		}
+	}
+
+	pub fn modify_guild_role(&self, guild_id: GuildId, role_id: RoleId) -> ModifyGuildRole<'_> {
+		ModifyGuildRole {
+			client: self,
+			guild_id,
+			role_id,
+			reason: None,
+			role: Default::default(),
+		}
+	}
+
+	pub fn delete_guild_role(&self, guild_id: GuildId, role_id: RoleId) -> AuditRequest<'_> {
+		AuditRequest::new(
+			self,
+			Method::DELETE,
+			format!("guilds/{}/roles/{}", guild_id, role_id),
+		)
+	}
+
+	pub fn modify_guild_role_positions<I>(
+		&self,
+		guild_id: GuildId,
+		positions: I,
+	) -> ModifyGuildRolePositions<'_>
+	where
+		I: IntoIterator<Item = (RoleId, u16)>,
+	{
+		ModifyGuildRolePositions {
+			client: self,
+			guild_id,
+			reason: None,
+			positions: positions
+				.into_iter()
+				.map(|(id, position)| body::RolePosition { id, position })
+				.collect(),
+		}
+	}
+}
+
+macro_rules! role_fields {
+	($t:ident) => {
+		impl<'a> $t<'a> {
+			pub fn name<T: Into<CowString>>(mut self, name: T) -> Self {
+				self.role.name = Some(name.into());
+				self
+			}
+
+			pub fn permissions(mut self, permissions: Permissions) -> Self {
+				self.role.permissions = Some(permissions);
+				self
+			}
+
+			/// RGB color, zero for no color
+			pub fn color(mut self, color: u32) -> Self {
+				self.role.color = Some(color);
+				self
+			}
+
+			/// Display the role separately in the member list
+			pub fn hoist(mut self, hoist: bool) -> Self {
+				self.role.hoist = Some(hoist);
+				self
+			}
+
+			pub fn mentionable(mut self, mentionable: bool) -> Self {
+				self.role.mentionable = Some(mentionable);
+				self
+			}
+
+			/// Role icon, requires the guild to have the `ROLE_ICONS` feature
+			pub fn icon(mut self, mime_type: &str, data: &[u8]) -> Self {
+				self.role.icon = Some(Some(body::image_data(mime_type, data)));
+				self
+			}
+
+			pub fn unicode_emoji<T: Into<CowString>>(mut self, emoji: T) -> Self {
+				self.role.unicode_emoji = Some(Some(emoji.into()));
+				self
+			}
+
+			pub fn reason(mut self, reason: &'a str) -> Self {
+				self.reason = Some(reason);
+				self
+			}
+		}
+	};
+}
+
+pub struct CreateGuildRole<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	reason: Option<&'a str>,
+	role: body::GuildRole,
+}
+
+role_fields!(CreateGuildRole);
+
+impl<'a> CreateGuildRole<'a> {
+	pub async fn send(self) -> Result<Role, Error> {
+		self.client
+			.post(
+				&format!("guilds/{}/roles", self.guild_id),
+				self.role,
+				self.reason,
+			)
+			.await
+	}
+}
+
+pub struct ModifyGuildRole<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	role_id: RoleId,
+	reason: Option<&'a str>,
+	role: body::GuildRole,
+}
+
+role_fields!(ModifyGuildRole);
+
+impl<'a> ModifyGuildRole<'a> {
+	pub fn clear_icon(mut self) -> Self {
+		self.role.icon = Some(None);
+		self
+	}
+
+	pub fn clear_unicode_emoji(mut self) -> Self {
+		self.role.unicode_emoji = Some(None);
+		self
+	}
+
+	pub async fn send(self) -> Result<Role, Error> {
+		self.client
+			.patch(
+				&format!("guilds/{}/roles/{}", self.guild_id, self.role_id),
+				self.role,
+				self.reason,
+			)
+			.await
+	}
+}
+
+pub struct ModifyGuildRolePositions<'a> {
+	client: &'a Client,
+	guild_id: GuildId,
+	reason: Option<&'a str>,
+	positions: Vec<body::RolePosition>,
+}
+
+impl<'a> ModifyGuildRolePositions<'a> {
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	/// Returns all roles of the guild, with their updated positions
+	pub async fn send(self) -> Result<Vec<Role>, Error> {
+		self.client
+			.patch(
+				&format!("guilds/{}/roles", self.guild_id),
+				self.positions,
+				self.reason,
+			)
+			.await
+	}
+}
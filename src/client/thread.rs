@@ -0,0 +1,385 @@
+use super::{
+	body, validate, Attachment, Attachments, Client, CowString, Error, ModifyChannel, RowComponent,
+	ValidationError,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use discord_types::{Channel, ChannelId, ChannelType, Embed, GuildId, Member, MessageId, UserId};
+use serde::Deserialize;
+use serde_repr::Serialize_repr;
+use std::fmt;
+
+/// Inactivity after which a thread is archived automatically
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize_repr)]
+#[repr(u16)]
+pub enum AutoArchiveDuration {
+	Hour = 60,
+	Day = 1440,
+	ThreeDays = 4320,
+	Week = 10080,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ThreadMember {
+	/// Omitted in the members of `ThreadList`
+	pub id: Option<ChannelId>,
+	pub user_id: Option<UserId>,
+	pub join_timestamp: String,
+	pub flags: u64,
+	/// Only present if requested with `with_member`
+	pub member: Option<Member>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ThreadList {
+	pub threads: Vec<Channel>,
+	/// Thread members of the current user, for the threads it has joined
+	pub members: Vec<ThreadMember>,
+	/// Whether there are more archived threads before the last one
+	#[serde(default)]
+	pub has_more: bool,
+}
+
+impl Client {
+	pub fn start_thread_from_message<T: Into<CowString>>(
+		&self,
+		channel_id: ChannelId,
+		message_id: MessageId,
+		name: T,
+	) -> StartThread<'_> {
+		StartThread::new(
+			self,
+			format!("channels/{}/messages/{}/threads", channel_id, message_id),
+			name.into(),
+			None,
+		)
+	}
+
+	/// Public thread that isn't attached to a message, see `StartThread::private`
+	pub fn start_thread<T: Into<CowString>>(
+		&self,
+		channel_id: ChannelId,
+		name: T,
+	) -> StartThread<'_> {
+		StartThread::new(
+			self,
+			format!("channels/{}/threads", channel_id),
+			name.into(),
+			Some(ChannelType::PublicThread),
+		)
+	}
+
+	/// New thread in a forum or media channel, with its first message
+	pub fn create_forum_post<T: Into<CowString>>(
+		&self,
+		channel_id: ChannelId,
+		name: T,
+	) -> CreateForumPost<'_> {
+		CreateForumPost {
+			client: self,
+			channel_id,
+			reason: None,
+			attachments: Default::default(),
+			cm: Default::default(),
+			st: body::StartThread {
+				name: name.into(),
+				channel_type: None,
+				auto_archive_duration: None,
+				rate_limit_per_user: None,
+				invitable: None,
+				applied_tags: None,
+				message: None,
+			},
+		}
+	}
+
+	pub fn archive_thread(&self, thread_id: ChannelId) -> ModifyChannel<'_> {
+		self.modify_channel(thread_id).archived(true)
+	}
+
+	pub async fn join_thread(&self, thread_id: ChannelId) -> Result<(), Error> {
+		self.put(
+			&format!("channels/{}/thread-members/@me", thread_id),
+			(),
+			None,
+		)
+		.await
+	}
+
+	pub async fn leave_thread(&self, thread_id: ChannelId) -> Result<(), Error> {
+		self.delete(&format!("channels/{}/thread-members/@me", thread_id), None)
+			.await
+	}
+
+	pub async fn add_thread_member(
+		&self,
+		thread_id: ChannelId,
+		user_id: UserId,
+	) -> Result<(), Error> {
+		self.put(
+			&format!("channels/{}/thread-members/{}", thread_id, user_id),
+			(),
+			None,
+		)
+		.await
+	}
+
+	pub async fn remove_thread_member(
+		&self,
+		thread_id: ChannelId,
+		user_id: UserId,
+	) -> Result<(), Error> {
+		self.delete(
+			&format!("channels/{}/thread-members/{}", thread_id, user_id),
+			None,
+		)
+		.await
+	}
+
+	pub async fn get_thread_member(
+		&self,
+		thread_id: ChannelId,
+		user_id: UserId,
+	) -> Result<ThreadMember, Error> {
+		self.get(&format!(
+			"channels/{}/thread-members/{}?with_member=true",
+			thread_id, user_id
+		))
+		.await
+	}
+
+	pub async fn get_thread_members(
+		&self,
+		thread_id: ChannelId,
+	) -> Result<Vec<ThreadMember>, Error> {
+		self.get(&format!(
+			"channels/{}/thread-members?with_member=true&limit=100",
+			thread_id
+		))
+		.await
+	}
+
+	/// All active threads in the guild that the bot can see
+	pub async fn get_active_threads(&self, guild_id: GuildId) -> Result<ThreadList, Error> {
+		self.get(&format!("guilds/{}/threads/active", guild_id))
+			.await
+	}
+
+	/// Archived public threads, most recently archived first
+	pub async fn get_public_archived_threads(
+		&self,
+		channel_id: ChannelId,
+		before: Option<DateTime<Utc>>,
+		limit: Option<u8>,
+	) -> Result<ThreadList, Error> {
+		self.get(&archived_url(
+			format!("channels/{}/threads/archived/public", channel_id),
+			before.map(timestamp),
+			limit,
+		))
+		.await
+	}
+
+	/// Archived private threads, requires `MANAGE_THREADS`
+	pub async fn get_private_archived_threads(
+		&self,
+		channel_id: ChannelId,
+		before: Option<DateTime<Utc>>,
+		limit: Option<u8>,
+	) -> Result<ThreadList, Error> {
+		self.get(&archived_url(
+			format!("channels/{}/threads/archived/private", channel_id),
+			before.map(timestamp),
+			limit,
+		))
+		.await
+	}
+
+	/// Archived private threads the bot has joined, in descending order of their id
+	pub async fn get_joined_private_archived_threads(
+		&self,
+		channel_id: ChannelId,
+		before: Option<ChannelId>,
+		limit: Option<u8>,
+	) -> Result<ThreadList, Error> {
+		self.get(&archived_url(
+			format!("channels/{}/users/@me/threads/archived/private", channel_id),
+			before,
+			limit,
+		))
+		.await
+	}
+}
+
+fn archived_url<T: fmt::Display>(mut url: String, before: Option<T>, limit: Option<u8>) -> String {
+	url.push_str(&format!("?limit={}", limit.unwrap_or(50)));
+	if let Some(before) = before {
+		url.push_str(&format!("&before={}", before));
+	}
+	url
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+	urlencoding::encode(&time.to_rfc3339_opts(SecondsFormat::Millis, true)).into_owned()
+}
+
+pub struct StartThread<'a> {
+	client: &'a Client,
+	url: String,
+	reason: Option<&'a str>,
+	private: bool,
+	st: body::StartThread,
+}
+
+impl<'a> StartThread<'a> {
+	fn new(
+		client: &'a Client,
+		url: String,
+		name: CowString,
+		channel_type: Option<ChannelType>,
+	) -> Self {
+		Self {
+			client,
+			url,
+			reason: None,
+			private: false,
+			st: body::StartThread {
+				name,
+				channel_type,
+				auto_archive_duration: None,
+				rate_limit_per_user: None,
+				invitable: None,
+				applied_tags: None,
+				message: None,
+			},
+		}
+	}
+
+	pub fn auto_archive_duration(mut self, duration: AutoArchiveDuration) -> Self {
+		self.st.auto_archive_duration = Some(duration);
+		self
+	}
+
+	/// Slowmode, in seconds
+	pub fn rate_limit_per_user(mut self, seconds: u16) -> Self {
+		self.st.rate_limit_per_user = Some(seconds);
+		self
+	}
+
+	/// Only for threads that aren't attached to a message, sending fails otherwise
+	pub fn private(mut self) -> Self {
+		self.private = true;
+		self
+	}
+
+	/// Whether non-moderators can add other members to a private thread
+	pub fn invitable(mut self, invitable: bool) -> Self {
+		self.st.invitable = Some(invitable);
+		self
+	}
+
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(mut self) -> Result<Channel, Error> {
+		if self.private {
+			// Threads started from a message are created without a type
+			if self.st.channel_type.is_none() {
+				return Err(ValidationError::Invalid(
+					"Threads started from a message can't be private".into(),
+				)
+				.into());
+			}
+			self.st.channel_type = Some(ChannelType::PrivateThread);
+		}
+		self.client.post(&self.url, self.st, self.reason).await
+	}
+}
+
+pub struct CreateForumPost<'a> {
+	client: &'a Client,
+	channel_id: ChannelId,
+	reason: Option<&'a str>,
+	attachments: Attachments,
+	cm: body::CreateMessage,
+	st: body::StartThread,
+}
+
+impl<'a> CreateForumPost<'a> {
+	pub fn content<T: Into<CowString>>(mut self, content: T) -> Self {
+		self.cm.content = Some(content.into());
+		self
+	}
+
+	pub fn embed(mut self, embed: Embed) -> Self {
+		self.cm.embeds.push(embed);
+		self
+	}
+
+	pub fn component_rows<T: IntoIterator<Item = RowComponent>>(mut self, rows: T) -> Self {
+		self.cm.components = rows.into_iter().map(|r| r.component).collect();
+		self
+	}
+
+	pub fn component_row<T: Into<RowComponent>>(mut self, row: T) -> Self {
+		self.cm.components.push(row.into().component);
+		self
+	}
+
+	pub fn attachment<T: Into<Attachment>>(mut self, attachment: T) -> Self {
+		self.attachments.push(attachment.into());
+		self
+	}
+
+	/// Apply one of the tags available in the forum
+	pub fn tag<T: fmt::Display>(mut self, tag_id: T) -> Self {
+		self.st
+			.applied_tags
+			.get_or_insert_with(Vec::new)
+			.push(tag_id.to_string());
+		self
+	}
+
+	pub fn auto_archive_duration(mut self, duration: AutoArchiveDuration) -> Self {
+		self.st.auto_archive_duration = Some(duration);
+		self
+	}
+
+	/// Slowmode, in seconds
+	pub fn rate_limit_per_user(mut self, seconds: u16) -> Self {
+		self.st.rate_limit_per_user = Some(seconds);
+		self
+	}
+
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	/// Returns the created thread, its id is also the id of the first message
+	pub async fn send(mut self) -> Result<Channel, Error> {
+		validate::message(
+			self.cm.content.as_deref(),
+			&self.cm.embeds,
+			&self.cm.components,
+		)?;
+		validate::count(
+			"attachments",
+			self.attachments.len(),
+			validate::MAX_ATTACHMENTS,
+		)?;
+		let url = format!("channels/{}/threads", self.channel_id);
+		// Attachment metadata belongs to the message, not to the thread
+		self.st.message = Some(self.attachments.json(self.cm)?);
+
+		if !self.attachments.has_files() {
+			return self.client.post(&url, self.st, self.reason).await;
+		}
+
+		let multipart = self.attachments.form(&self.st)?;
+		self.client
+			.post_multipart(&url, multipart, self.reason)
+			.await
+	}
+}
@@ -0,0 +1,114 @@
+use super::{body, Client, CowString, Error};
+use discord_types::{Channel, GuildId, Permissions, User, UserId};
+use serde::Deserialize;
+
+/// Guild as returned by `Client::get_current_user_guilds`
+#[derive(Clone, Debug, Deserialize)]
+pub struct CurrentUserGuild {
+	pub id: GuildId,
+	pub name: String,
+	pub icon: Option<String>,
+	/// Whether the current user owns the guild
+	pub owner: bool,
+	/// Permissions of the current user in the guild
+	pub permissions: Permissions,
+	#[serde(default)]
+	pub features: Vec<String>,
+}
+
+impl Client {
+	pub async fn get_user(&self, user_id: UserId) -> Result<User, Error> {
+		self.get(&format!("users/{}", user_id)).await
+	}
+
+	pub async fn get_current_user(&self) -> Result<User, Error> {
+		self.get("users/@me").await
+	}
+
+	pub fn modify_current_user(&self) -> ModifyCurrentUser<'_> {
+		ModifyCurrentUser {
+			client: self,
+			mcu: Default::default(),
+		}
+	}
+
+	/// Guilds the current user is in, ordered by id
+	pub async fn get_current_user_guilds(
+		&self,
+		after: Option<GuildId>,
+		limit: Option<u8>,
+	) -> Result<Vec<CurrentUserGuild>, Error> {
+		let limit = limit.unwrap_or(200).min(200);
+		let param = match after {
+			Some(a) => format!("&after={}", a),
+			None => String::new(),
+		};
+		self.get(&format!("users/@me/guilds?limit={}{}", limit, param))
+			.await
+	}
+
+	pub async fn leave_guild(&self, guild_id: GuildId) -> Result<(), Error> {
+		self.delete(&format!("users/@me/guilds/{}", guild_id), None)
+			.await
+	}
+
+	/// DM channel with the user, only opened once per user for the lifetime of the client
+	pub async fn create_dm(&self, user_id: UserId) -> Result<Channel, Error> {
+		let cell = self
+			.dm_channels
+			.lock()
+			.unwrap()
+			.entry(user_id)
+			.or_default()
+			.clone();
+		// A failed request leaves the cell empty, so the next call tries again
+		let channel = cell
+			.get_or_try_init(|| {
+				self.post(
+					"users/@me/channels",
+					body::CreateDm {
+						recipient_id: user_id,
+					},
+					None,
+				)
+			})
+			.await?;
+		Ok(channel.clone())
+	}
+}
+
+pub struct ModifyCurrentUser<'a> {
+	client: &'a Client,
+	mcu: body::ModifyCurrentUser,
+}
+
+impl<'a> ModifyCurrentUser<'a> {
+	pub fn username<T: Into<CowString>>(mut self, username: T) -> Self {
+		self.mcu.username = Some(username.into());
+		self
+	}
+
+	pub fn avatar(mut self, mime_type: &str, data: &[u8]) -> Self {
+		self.mcu.avatar = Some(Some(body::image_data(mime_type, data)));
+		self
+	}
+
+	pub fn clear_avatar(mut self) -> Self {
+		self.mcu.avatar = Some(None);
+		self
+	}
+
+	pub fn banner(mut self, mime_type: &str, data: &[u8]) -> Self {
+		self.mcu.banner = Some(Some(body::image_data(mime_type, data)));
+		self
+	}
+
+	pub fn clear_banner(mut self) -> Self {
+		self.mcu.banner = Some(None);
+		self
+	}
+
+	pub async fn send(self) -> Result<User, Error> {
+		self.client.patch("users/@me", self.mcu, None).await
+	}
+}
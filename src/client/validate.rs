@@ -0,0 +1,316 @@
+//! Client-side checks of Discord's documented limits, so invalid requests fail
+//! with a descriptive error instead of an opaque `BadRequest`
+
+use discord_types::{Component, Embed};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+pub(crate) const MAX_CONTENT: usize = 2000;
+const MAX_EMBEDS: usize = 10;
+const MAX_EMBED_TOTAL: usize = 6000;
+const MAX_EMBED_FIELDS: usize = 25;
+const MAX_ROWS: usize = 5;
+const MAX_ROW_COMPONENTS: usize = 5;
+const MAX_SELECT_OPTIONS: usize = 25;
+pub(crate) const MAX_STICKERS: usize = 3;
+pub(crate) const MAX_ATTACHMENTS: usize = 10;
+pub(crate) const MAX_CHOICES: usize = 25;
+
+/// Embed fields and their maximum length, all of them count towards the total of an embed
+const EMBED_TEXT: [(&str, &str, usize); 4] = [
+	("embed title", "/title", 256),
+	("embed description", "/description", 4096),
+	("embed footer", "/footer/text", 2048),
+	("embed author", "/author/name", 256),
+];
+
+const COMPONENT_TEXT: [(&str, &str, usize); 3] = [
+	("component label", "/label", 80),
+	("component custom id", "/custom_id", 100),
+	("select menu placeholder", "/placeholder", 150),
+];
+
+const OPTION_TEXT: [(&str, &str, usize); 3] = [
+	("select option label", "/label", 100),
+	("select option value", "/value", 100),
+	("select option description", "/description", 100),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+	/// Text is longer than allowed, lengths are in characters
+	TooLong {
+		field: &'static str,
+		len: usize,
+		max: usize,
+	},
+	TooMany {
+		field: &'static str,
+		count: usize,
+		max: usize,
+	},
+	/// Combination of values that Discord rejects
+	Invalid(String),
+}
+
+impl fmt::Display for ValidationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ValidationError::TooLong { field, len, max } => {
+				write!(f, "{} is too long ({}/{} characters)", field, len, max)
+			}
+			ValidationError::TooMany { field, count, max } => {
+				write!(f, "Too many {} ({}/{})", field, count, max)
+			}
+			ValidationError::Invalid(reason) => f.write_str(reason),
+		}
+	}
+}
+
+impl std::error::Error for ValidationError {}
+
+pub(crate) fn len(field: &'static str, text: &str, max: usize) -> Result<usize, ValidationError> {
+	let len = text.chars().count();
+	if len > max {
+		return Err(ValidationError::TooLong { field, len, max });
+	}
+	Ok(len)
+}
+
+pub(crate) fn count(field: &'static str, count: usize, max: usize) -> Result<(), ValidationError> {
+	if count > max {
+		return Err(ValidationError::TooMany { field, count, max });
+	}
+	Ok(())
+}
+
+pub(crate) fn message(
+	content: Option<&str>,
+	embeds: &[Embed],
+	rows: &[Component],
+) -> Result<(), ValidationError> {
+	if let Some(content) = content {
+		len("content", content, MAX_CONTENT)?;
+	}
+	self::embeds(embeds)?;
+	components(rows)
+}
+
+fn embeds(embeds: &[Embed]) -> Result<(), ValidationError> {
+	count("embeds", embeds.len(), MAX_EMBEDS)?;
+
+	let mut total = 0;
+	for embed in embeds.iter().map(json) {
+		total += embed_len(&embed)?;
+	}
+	embed_total("embeds", total)
+}
+
+/// Check the limits of a single serialized embed, returns its length in characters
+pub(crate) fn embed_len(embed: &Value) -> Result<usize, ValidationError> {
+	let mut total = 0;
+	for (field, pointer, max) in EMBED_TEXT {
+		total += text_len(embed, field, pointer, max)?;
+	}
+
+	let fields = array(embed, "/fields");
+	count("embed fields", fields.len(), MAX_EMBED_FIELDS)?;
+	for field in fields {
+		total += text_len(field, "embed field name", "/name", 256)?;
+		total += text_len(field, "embed field value", "/value", 1024)?;
+	}
+	Ok(total)
+}
+
+pub(crate) fn embed_total(field: &'static str, total: usize) -> Result<(), ValidationError> {
+	if total > MAX_EMBED_TOTAL {
+		return Err(ValidationError::TooLong {
+			field,
+			len: total,
+			max: MAX_EMBED_TOTAL,
+		});
+	}
+	Ok(())
+}
+
+fn components(rows: &[Component]) -> Result<(), ValidationError> {
+	count("component rows", rows.len(), MAX_ROWS)?;
+
+	for row in rows.iter().map(json) {
+		let components = array(&row, "/components");
+		count("components in a row", components.len(), MAX_ROW_COMPONENTS)?;
+		for component in components {
+			for (field, pointer, max) in COMPONENT_TEXT {
+				text_len(component, field, pointer, max)?;
+			}
+
+			let options = array(component, "/options");
+			count("select options", options.len(), MAX_SELECT_OPTIONS)?;
+			for option in options {
+				for (field, pointer, max) in OPTION_TEXT {
+					text_len(option, field, pointer, max)?;
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+/// The fields of the types in `discord_types` aren't all public,
+/// so we check their serialized form instead
+fn json<T: Serialize>(value: &T) -> Value {
+	serde_json::to_value(value).unwrap_or_default()
+}
+
+fn text_len(
+	value: &Value,
+	field: &'static str,
+	pointer: &str,
+	max: usize,
+) -> Result<usize, ValidationError> {
+	match value.pointer(pointer).and_then(Value::as_str) {
+		Some(text) => len(field, text, max),
+		None => Ok(0),
+	}
+}
+
+fn array<'a>(value: &'a Value, pointer: &str) -> &'a [Value] {
+	value
+		.pointer(pointer)
+		.and_then(Value::as_array)
+		.map(Vec::as_slice)
+		.unwrap_or(&[])
+}
+
+/// Split `content` into chunks of at most `max` characters, on line breaks where possible.
+/// A code block that is split is closed at the end of the chunk and reopened in the next one.
+/// Chunks that would only hold whitespace are dropped
+pub(crate) fn split(content: &str, max: usize) -> Vec<String> {
+	let mut chunks = Vec::new();
+	let mut chunk = String::new();
+	let mut chunk_len = 0;
+	// Whether the chunk holds more than whitespace and the reopened code block
+	let mut has_text = false;
+	// Opening line of the code block we're in, if any
+	let mut fence: Option<String> = None;
+
+	for line in content.split_inclusive('\n') {
+		let fence_len = fence.as_ref().map(|f| f.chars().count() + 1).unwrap_or(0);
+		// Room for reopening and closing the code block
+		let budget = max
+			.saturating_sub(fence_len + if fence.is_some() { 4 } else { 0 })
+			.max(1);
+
+		for piece in split_line(line, budget) {
+			let piece_len = piece.chars().count();
+			let closing = if fence.is_some() { 4 } else { 0 };
+			if chunk_len > fence_len && chunk_len + piece_len + closing > max {
+				if has_text {
+					if fence.is_some() {
+						if !chunk.ends_with('\n') {
+							chunk.push('\n');
+						}
+						chunk.push_str("```");
+					}
+					chunks.push(std::mem::take(&mut chunk));
+				}
+				chunk.clear();
+				chunk_len = 0;
+				has_text = false;
+				if let Some(fence) = &fence {
+					chunk.push_str(fence);
+					chunk.push('\n');
+					chunk_len = fence_len;
+				}
+			}
+			chunk.push_str(piece);
+			chunk_len += piece_len;
+			has_text |= !piece.trim().is_empty();
+		}
+
+		if line.matches("```").count() % 2 == 1 {
+			fence = match fence {
+				Some(_) => None,
+				None => line.find("```").map(|i| line[i..].trim_end().to_owned()),
+			};
+		}
+	}
+
+	if has_text {
+		chunks.push(chunk);
+	}
+	chunks
+		.into_iter()
+		.map(|c| c.trim_matches('\n').to_owned())
+		.collect()
+}
+
+/// Split a single line into pieces of at most `max` characters, preferably after whitespace
+fn split_line(line: &str, max: usize) -> Vec<&str> {
+	let mut pieces = Vec::new();
+	let mut rest = line;
+	while rest.chars().count() > max {
+		let end = rest
+			.char_indices()
+			.nth(max)
+			.map(|(i, _)| i)
+			.unwrap_or(rest.len());
+		let split = rest[..end]
+			.rfind(char::is_whitespace)
+			.map(|i| i + rest[i..].chars().next().map(char::len_utf8).unwrap_or(1))
+			.filter(|&i| i > end / 2)
+			.unwrap_or(end);
+		pieces.push(&rest[..split]);
+		rest = &rest[split..];
+	}
+	pieces.push(rest);
+	pieces
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn content_length() {
+		assert!(message(Some(&"a".repeat(2000)), &[], &[]).is_ok());
+		assert_eq!(
+			message(Some(&"é".repeat(2001)), &[], &[]),
+			Err(ValidationError::TooLong {
+				field: "content",
+				len: 2001,
+				max: 2000
+			})
+		);
+	}
+
+	#[test]
+	fn split_lines() {
+		let content = format!("{}\n{}\n{}", "a".repeat(6), "b".repeat(6), "c".repeat(3));
+		assert_eq!(split(&content, 10), ["aaaaaa", "bbbbbb\nccc"]);
+		assert_eq!(split(&"a".repeat(25), 10).len(), 3);
+		assert_eq!(split("short", 10), ["short"]);
+	}
+
+	#[test]
+	fn split_blank_lines() {
+		assert_eq!(
+			split("aaaaaaaaa\n\n\nbbbbbbbbb", 10),
+			["aaaaaaaaa", "bbbbbbbbb"]
+		);
+		assert!(split(&"\n".repeat(30), 10).is_empty());
+		assert_eq!(split(&format!("a{}b", "\n".repeat(30)), 10), ["a", "b"]);
+		let chunks = split(&format!("a{}b", " \n".repeat(15)), 10);
+		assert!(chunks.iter().all(|c| !c.trim().is_empty()));
+	}
+
+	#[test]
+	fn split_code_block() {
+		let content = "text\n```rust\nlet a = 1;\nlet b = 2;\n```\nend";
+		let chunks = split(content, 30);
+		assert!(chunks.iter().all(|c| c.chars().count() <= 30));
+		assert!(chunks.iter().all(|c| c.matches("```").count() % 2 == 0));
+		assert_eq!(chunks[1], "```rust\nlet b = 2;\n```\nend");
+	}
+}
@@ -0,0 +1,479 @@
+use super::{
+	body, decode, json, validate, Attachment, Attachments, AuditRequest, Client, CowString, Error,
+	RowComponent,
+};
+use discord_types::{
+	AllowedMentions, ApplicationId, ChannelId, Embed, GuildId, Message, MessageId, User,
+};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_repr::Deserialize_repr;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize_repr)]
+#[repr(u8)]
+pub enum WebhookType {
+	Incoming = 1,
+	ChannelFollower = 2,
+	Application = 3,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Webhook {
+	pub id: String,
+	#[serde(rename = "type")]
+	pub webhook_type: WebhookType,
+	pub guild_id: Option<GuildId>,
+	pub channel_id: Option<ChannelId>,
+	/// User that created the webhook, not returned when fetched with its token
+	pub user: Option<User>,
+	pub name: Option<String>,
+	pub avatar: Option<String>,
+	/// Only present for incoming webhooks
+	pub token: Option<String>,
+	pub application_id: Option<ApplicationId>,
+	pub url: Option<String>,
+}
+
+impl Webhook {
+	/// Execute the webhook, if its token is known
+	pub fn execute<'a>(&self, client: &'a Client) -> Option<ExecuteWebhook<'a>> {
+		let token = self.token.as_ref()?;
+		Some(client.execute_webhook(&self.id, token))
+	}
+}
+
+impl Client {
+	pub fn create_webhook<T: Into<CowString>>(
+		&self,
+		channel_id: ChannelId,
+		name: T,
+	) -> CreateWebhook<'_> {
+		CreateWebhook {
+			client: self,
+			channel_id,
+			reason: None,
+			cw: body::CreateWebhook {
+				name: name.into(),
+				avatar: None,
+			},
+		}
+	}
+
+	pub async fn get_channel_webhooks(&self, channel_id: ChannelId) -> Result<Vec<Webhook>, Error> {
+		self.get(&format!("channels/{}/webhooks", channel_id)).await
+	}
+
+	pub async fn get_guild_webhooks(&self, guild_id: GuildId) -> Result<Vec<Webhook>, Error> {
+		self.get(&format!("guilds/{}/webhooks", guild_id)).await
+	}
+
+	pub async fn get_webhook<T: fmt::Display>(&self, webhook_id: T) -> Result<Webhook, Error> {
+		self.get(&format!("webhooks/{}", webhook_id)).await
+	}
+
+	/// Doesn't require a bot token
+	pub async fn get_webhook_with_token<T: fmt::Display>(
+		&self,
+		webhook_id: T,
+		token: &str,
+	) -> Result<Webhook, Error> {
+		self.get(&format!("webhooks/{}/{}", webhook_id, token))
+			.await
+	}
+
+	pub fn modify_webhook<T: fmt::Display>(&self, webhook_id: T) -> ModifyWebhook<'_> {
+		ModifyWebhook {
+			client: self,
+			path: format!("webhooks/{}", webhook_id),
+			reason: None,
+			mw: Default::default(),
+		}
+	}
+
+	/// Doesn't require a bot token, but the channel can't be changed
+	pub fn modify_webhook_with_token<T: fmt::Display>(
+		&self,
+		webhook_id: T,
+		token: &str,
+	) -> ModifyWebhook<'_> {
+		ModifyWebhook {
+			client: self,
+			path: format!("webhooks/{}/{}", webhook_id, token),
+			reason: None,
+			mw: Default::default(),
+		}
+	}
+
+	pub fn delete_webhook<T: fmt::Display>(&self, webhook_id: T) -> AuditRequest<'_> {
+		AuditRequest::new(self, Method::DELETE, format!("webhooks/{}", webhook_id))
+	}
+
+	/// Doesn't require a bot token
+	pub async fn delete_webhook_with_token<T: fmt::Display>(
+		&self,
+		webhook_id: T,
+		token: &str,
+	) -> Result<(), Error> {
+		self.delete(&format!("webhooks/{}/{}", webhook_id, token), None)
+			.await
+	}
+
+	pub fn execute_webhook<T: fmt::Display>(
+		&self,
+		webhook_id: T,
+		token: &str,
+	) -> ExecuteWebhook<'_> {
+		ExecuteWebhook {
+			client: self,
+			path: format!("webhooks/{}/{}", webhook_id, token),
+			thread_id: None,
+			wait: false,
+			attachments: Default::default(),
+			ew: Default::default(),
+		}
+	}
+
+	/// Execute a webhook by its URL, e.g. `https://discord.com/api/webhooks/{id}/{token}`.
+	/// Works with a client built by `ClientBuilder::unauthenticated`
+	pub fn execute_webhook_url(&self, url: &str) -> Result<ExecuteWebhook<'_>, Error> {
+		let (webhook_id, token) = parse_webhook_url(url).ok_or(Error::BadRequest)?;
+		Ok(self.execute_webhook(webhook_id, token))
+	}
+
+	pub async fn get_webhook_message<T: fmt::Display>(
+		&self,
+		webhook_id: T,
+		token: &str,
+		message_id: MessageId,
+	) -> Result<Message, Error> {
+		self.get(&format!(
+			"webhooks/{}/{}/messages/{}",
+			webhook_id, token, message_id
+		))
+		.await
+	}
+
+	pub fn edit_webhook_message<T: fmt::Display>(
+		&self,
+		webhook_id: T,
+		token: &str,
+		message_id: MessageId,
+	) -> EditWebhookMessage<'_> {
+		EditWebhookMessage {
+			client: self,
+			path: format!("webhooks/{}/{}/messages/{}", webhook_id, token, message_id),
+			thread_id: None,
+			ewm: Default::default(),
+		}
+	}
+
+	pub async fn delete_webhook_message<T: fmt::Display>(
+		&self,
+		webhook_id: T,
+		token: &str,
+		message_id: MessageId,
+	) -> Result<(), Error> {
+		self.delete(
+			&format!("webhooks/{}/{}/messages/{}", webhook_id, token, message_id),
+			None,
+		)
+		.await
+	}
+}
+
+/// Id and token of a webhook URL
+fn parse_webhook_url(url: &str) -> Option<(&str, &str)> {
+	let path = url.split(['?', '#']).next()?.trim_end_matches('/');
+	let mut segments = path.rsplit('/');
+	let token = segments.next()?;
+	let webhook_id = segments.next()?;
+	if segments.next()? != "webhooks"
+		|| token.is_empty()
+		|| webhook_id.is_empty()
+		|| !webhook_id.bytes().all(|b| b.is_ascii_digit())
+	{
+		return None;
+	}
+	Some((webhook_id, token))
+}
+
+pub struct CreateWebhook<'a> {
+	client: &'a Client,
+	channel_id: ChannelId,
+	reason: Option<&'a str>,
+	cw: body::CreateWebhook,
+}
+
+impl<'a> CreateWebhook<'a> {
+	pub fn avatar(mut self, mime_type: &str, data: &[u8]) -> Self {
+		self.cw.avatar = Some(body::image_data(mime_type, data));
+		self
+	}
+
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<Webhook, Error> {
+		self.client
+			.post(
+				&format!("channels/{}/webhooks", self.channel_id),
+				self.cw,
+				self.reason,
+			)
+			.await
+	}
+}
+
+pub struct ModifyWebhook<'a> {
+	client: &'a Client,
+	path: String,
+	reason: Option<&'a str>,
+	mw: body::ModifyWebhook,
+}
+
+impl<'a> ModifyWebhook<'a> {
+	pub fn name<T: Into<CowString>>(mut self, name: T) -> Self {
+		self.mw.name = Some(name.into());
+		self
+	}
+
+	pub fn avatar(mut self, mime_type: &str, data: &[u8]) -> Self {
+		self.mw.avatar = Some(Some(body::image_data(mime_type, data)));
+		self
+	}
+
+	pub fn clear_avatar(mut self) -> Self {
+		self.mw.avatar = Some(None);
+		self
+	}
+
+	/// Move the webhook to another channel. Requires a bot token
+	pub fn channel(mut self, channel_id: ChannelId) -> Self {
+		self.mw.channel_id = Some(channel_id);
+		self
+	}
+
+	pub fn reason(mut self, reason: &'a str) -> Self {
+		self.reason = Some(reason);
+		self
+	}
+
+	pub async fn send(self) -> Result<Webhook, Error> {
+		self.client.patch(&self.path, self.mw, self.reason).await
+	}
+}
+
+pub struct ExecuteWebhook<'a> {
+	client: &'a Client,
+	path: String,
+	thread_id: Option<ChannelId>,
+	wait: bool,
+	attachments: Attachments,
+	ew: body::ExecuteWebhook,
+}
+
+impl<'a> ExecuteWebhook<'a> {
+	pub fn content<T: Into<CowString>>(mut self, content: T) -> Self {
+		self.ew.content = Some(content.into());
+		self
+	}
+
+	/// Override the default username of the webhook
+	pub fn username<T: Into<CowString>>(mut self, username: T) -> Self {
+		self.ew.username = Some(username.into());
+		self
+	}
+
+	/// Override the default avatar of the webhook
+	pub fn avatar_url<T: Into<CowString>>(mut self, avatar_url: T) -> Self {
+		self.ew.avatar_url = Some(avatar_url.into());
+		self
+	}
+
+	pub fn tts(mut self) -> Self {
+		self.ew.tts = Some(true);
+		self
+	}
+
+	pub fn embed(mut self, embed: Embed) -> Self {
+		self.ew.embeds.push(embed);
+		self
+	}
+
+	pub fn embeds<I: IntoIterator<Item = Embed>>(mut self, embeds: I) -> Self {
+		self.ew.embeds = embeds.into_iter().collect();
+		self
+	}
+
+	/// Only non-interactive components, unless the webhook is owned by an application
+	pub fn component_rows<T: IntoIterator<Item = RowComponent>>(mut self, rows: T) -> Self {
+		self.ew.components = rows.into_iter().map(|r| r.component).collect();
+		self
+	}
+
+	pub fn component_row<T: Into<RowComponent>>(mut self, row: T) -> Self {
+		self.ew.components.push(row.into().component);
+		self
+	}
+
+	pub fn allowed_mentions(mut self, m: AllowedMentions) -> Self {
+		self.ew.allowed_mentions = Some(m);
+		self
+	}
+
+	pub fn attachment<T: Into<Attachment>>(mut self, attachment: T) -> Self {
+		self.attachments.push(attachment.into());
+		self
+	}
+
+	/// Send the message to a thread in the webhook's channel
+	pub fn thread(mut self, thread_id: ChannelId) -> Self {
+		self.thread_id = Some(thread_id);
+		self
+	}
+
+	/// Create a new thread with this name, if the webhook's channel is a forum
+	pub fn thread_name<T: Into<CowString>>(mut self, name: T) -> Self {
+		self.ew.thread_name = Some(name.into());
+		self
+	}
+
+	/// Wait for the message to be created, `send` then returns it
+	pub fn wait(mut self) -> Self {
+		self.wait = true;
+		self
+	}
+
+	/// Returns the created message if `wait` was set
+	pub async fn send(self) -> Result<Option<Message>, Error> {
+		validate::message(
+			self.ew.content.as_deref(),
+			&self.ew.embeds,
+			&self.ew.components,
+		)?;
+		if let Some(username) = &self.ew.username {
+			validate::len("username", username, 80)?;
+		}
+		validate::count(
+			"attachments",
+			self.attachments.len(),
+			validate::MAX_ATTACHMENTS,
+		)?;
+
+		let mut url = format!("{}?wait={}", self.path, self.wait);
+		if let Some(thread_id) = self.thread_id {
+			url.push_str(&format!("&thread_id={}", thread_id));
+		}
+
+		let res = if !self.attachments.has_files() {
+			self.client
+				.send(Method::POST, &url, |r| json(r, &self.ew))
+				.await?
+		} else {
+			let multipart = self.attachments.multipart(self.ew)?;
+			self.client
+				.send(Method::POST, &url, |r| r.multipart(multipart))
+				.await?
+		};
+
+		if self.wait {
+			decode(res).await.map(Some)
+		} else {
+			Ok(None)
+		}
+	}
+}
+
+pub struct EditWebhookMessage<'a> {
+	client: &'a Client,
+	path: String,
+	thread_id: Option<ChannelId>,
+	ewm: body::EditWebhookMessage,
+}
+
+impl<'a> EditWebhookMessage<'a> {
+	pub fn content<T: Into<CowString>>(mut self, content: T) -> Self {
+		self.ewm.content = Some(Some(content.into()));
+		self
+	}
+
+	pub fn clear_content(mut self) -> Self {
+		self.ewm.content = Some(None);
+		self
+	}
+
+	pub fn embed(mut self, embed: Embed) -> Self {
+		self.ewm.embeds.get_or_insert_with(Vec::new).push(embed);
+		self
+	}
+
+	pub fn embeds<I: IntoIterator<Item = Embed>>(mut self, embeds: I) -> Self {
+		self.ewm.embeds = Some(embeds.into_iter().collect());
+		self
+	}
+
+	pub fn component_rows<T: IntoIterator<Item = RowComponent>>(mut self, rows: T) -> Self {
+		self.ewm.components = Some(rows.into_iter().map(|r| r.component).collect());
+		self
+	}
+
+	pub fn component_row<T: Into<RowComponent>>(mut self, row: T) -> Self {
+		self.ewm
+			.components
+			.get_or_insert_with(Vec::new)
+			.push(row.into().component);
+		self
+	}
+
+	pub fn allowed_mentions(mut self, m: AllowedMentions) -> Self {
+		self.ewm.allowed_mentions = Some(m);
+		self
+	}
+
+	/// Thread the message was sent to
+	pub fn thread(mut self, thread_id: ChannelId) -> Self {
+		self.thread_id = Some(thread_id);
+		self
+	}
+
+	pub async fn send(self) -> Result<Message, Error> {
+		validate::message(
+			self.ewm.content.as_ref().and_then(|c| c.as_deref()),
+			self.ewm.embeds.as_deref().unwrap_or(&[]),
+			self.ewm.components.as_deref().unwrap_or(&[]),
+		)?;
+		let url = match self.thread_id {
+			Some(thread_id) => format!("{}?thread_id={}", self.path, thread_id),
+			None => self.path,
+		};
+		self.client.patch(&url, self.ewm, None).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn webhook_url() {
+		assert_eq!(
+			parse_webhook_url("https://discord.com/api/webhooks/1234/abc-DEF_123"),
+			Some(("1234", "abc-DEF_123"))
+		);
+		assert_eq!(
+			parse_webhook_url("https://discord.com/api/v10/webhooks/1234/token/?wait=true"),
+			Some(("1234", "token"))
+		);
+		assert_eq!(
+			parse_webhook_url("https://discord.com/api/webhooks/1234"),
+			None
+		);
+		assert_eq!(
+			parse_webhook_url("https://discord.com/api/channels/1234/token"),
+			None
+		);
+	}
+}
@@ -1,11 +1,23 @@
 use super::Codec;
 use crate::GatewayError;
 use async_tungstenite::tungstenite::Message;
+use discord_types::Payload;
 use log::warn;
 use std::fs;
 use std::io::Write;
 use std::marker::PhantomData;
 
+/// Types the `JsonCodec` can decode from the text of a message
+pub trait FromJson: Sized {
+	fn from_json(s: &str) -> Result<Self, serde_json::Error>;
+}
+
+impl FromJson for Payload {
+	fn from_json(s: &str) -> Result<Self, serde_json::Error> {
+		serde_json::from_str(s)
+	}
+}
+
 pub struct JsonCodec<S, D> {
 	file: Option<fs::File>,
 	s: PhantomData<S>,
@@ -31,7 +43,7 @@ impl<S, D> JsonCodec<S, D> {
 impl<S, D> Codec<S, D> for JsonCodec<S, D>
 where
 	S: serde::Serialize + Send + Sync,
-	for<'a> D: serde::Deserialize<'a> + Send + Sync + std::fmt::Debug,
+	D: FromJson + Send + Sync + std::fmt::Debug,
 {
 	fn encode(&mut self, command: S) -> Result<Message, GatewayError> {
 		let s = serde_json::to_string(&command)?;
@@ -53,7 +65,7 @@ where
 					file.write_all("\n".as_bytes()).unwrap();
 					file.sync_all().unwrap();
 				}
-				serde_json::from_str(&s).map_err(|e| e.into())
+				D::from_json(&s).map_err(|e| e.into())
 			}
 			Message::Close(frame) => Err(GatewayError::Close(frame)),
 			m => {
@@ -68,7 +80,7 @@ where
 mod tests {
 	use super::*;
 	use discord_types::command;
-	use discord_types::{Command, Intents, Payload};
+	use discord_types::{Command, Intents};
 	use serde_json::Value;
 	use std::str::FromStr;
 
@@ -1,4 +1,4 @@
-pub use self::json::JsonCodec;
+pub use self::json::{FromJson, JsonCodec};
 use crate::GatewayError;
 use async_tungstenite::tokio::connect_async;
 use async_tungstenite::tokio::ConnectStream;
@@ -318,14 +318,9 @@ async fn read<F: Callback<G>, G: Fut>(
 	callback: &mut F,
 	sequence: Arc<AtomicU64>,
 ) -> Result<Never, GatewayError> {
-	while let Some(event) = gateway.next().await {
-		let event = match event {
-			Ok(p) => {
-				if let Some(seq) = p.sequence {
-					sequence.store(seq, Ordering::Relaxed);
-				}
-				p.event
-			}
+	while let Some(payload) = gateway.next().await {
+		let payload = match payload {
+			Ok(p) => p,
 			Err(GatewayError::Close(frame)) => return Err(GatewayError::Close(frame)),
 			Err(e) => {
 				warn!("Gateway error: {}", e);
@@ -333,7 +328,13 @@ async fn read<F: Callback<G>, G: Fut>(
 			}
 		};
 
-		callback(GatewayEvent::Event(event)).await?;
+		if let Some(seq) = payload.payload.sequence {
+			sequence.store(seq, Ordering::Relaxed);
+		}
+		callback(GatewayEvent::Event(payload.payload.event)).await?;
+		if let Some(interaction) = payload.interaction {
+			callback(GatewayEvent::Interaction(interaction)).await?;
+		}
 	}
 
 	debug!("Reader shutdown");
@@ -1,4 +1,5 @@
-use crate::codec::{Codec, Connection, JsonCodec};
+use crate::codec::{Codec, Connection, FromJson, JsonCodec};
+use crate::interaction::RawInteraction;
 use crate::GatewayError;
 use async_tungstenite::tungstenite;
 use discord_types::event::EventError;
@@ -6,8 +7,9 @@ use discord_types::{command, event};
 use discord_types::{Command, Intents, Payload};
 use futures::stream::FusedStream;
 use futures::{Sink, SinkExt, Stream, StreamExt};
-use log::{debug, info};
+use log::{debug, info, warn};
 use pin_project::pin_project;
+use serde::Deserialize;
 use std::fmt;
 use std::pin::Pin;
 use std::task::{Context, Poll};
@@ -74,7 +76,7 @@ impl From<serde_json::Error> for Error {
 macro_rules! read_event {
 	($conn:expr) => {
 		match $conn.next().await {
-			Some(Ok(payload)) => payload.event,
+			Some(Ok(payload)) => payload.payload.event,
 			Some(Err(e)) => return Err(e.into()),
 			None => return Err(Error::Ws(tungstenite::Error::ConnectionClosed)),
 		}
@@ -151,7 +153,7 @@ impl<'a> Connector<'a> {
 				} else {
 					None
 				};
-				Box::new(JsonCodec::new(file)) as Box<dyn Codec<Command, Payload>>
+				Box::new(JsonCodec::new(file)) as Box<dyn Codec<Command, GatewayPayload>>
 			}
 		};
 
@@ -245,6 +247,8 @@ pub enum GatewayEvent {
 	Online,
 	SessionInvalidated,
 	Event(event::Event),
+	/// Raw form of the `Event::InteractionCreate` sent right before it
+	Interaction(RawInteraction),
 }
 
 impl From<event::Event> for GatewayEvent {
@@ -259,10 +263,43 @@ impl From<event::GuildCreate> for GatewayEvent {
 	}
 }
 
+/// Payload along with the raw interaction of an `INTERACTION_CREATE`
+#[derive(Debug)]
+pub struct GatewayPayload {
+	pub payload: Payload,
+	pub interaction: Option<RawInteraction>,
+}
+
+impl FromJson for GatewayPayload {
+	fn from_json(s: &str) -> Result<Self, serde_json::Error> {
+		#[derive(Deserialize)]
+		struct RawPayload {
+			d: RawInteraction,
+		}
+
+		let payload: Payload = serde_json::from_str(s)?;
+		// Only interactions are parsed a second time, to keep the fields `Event` drops
+		let interaction = match &payload.event {
+			event::Event::InteractionCreate(_) => match serde_json::from_str::<RawPayload>(s) {
+				Ok(raw) => Some(raw.d),
+				Err(e) => {
+					warn!("Unable to parse raw interaction: {}", e);
+					None
+				}
+			},
+			_ => None,
+		};
+		Ok(Self {
+			payload,
+			interaction,
+		})
+	}
+}
+
 #[pin_project]
 pub struct Gateway {
 	#[pin]
-	conn: Connection<Command, Payload>,
+	conn: Connection<Command, GatewayPayload>,
 	finished: bool,
 }
 
@@ -276,7 +313,7 @@ impl Gateway {
 // Return a `None` value forever after a single `None` or a WS error
 // TODO: do we need this?
 impl Stream for Gateway {
-	type Item = Result<Payload, Error>;
+	type Item = Result<GatewayPayload, Error>;
 
 	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
 		let project = self.project();
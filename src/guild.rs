@@ -1,10 +1,11 @@
+use crate::client::{CommandChange, CommandRegistry, CommandType};
 #[cfg(feature = "voice")]
 use crate::voice;
 use crate::{Client, Error, GatewayEvent};
 use discord_types::event;
 use discord_types::{
-	ApplicationCommand, ApplicationId, Channel, ChannelId, Event, GuildId, Member, Role, RoleId,
-	UserId,
+	ApplicationCommand, ApplicationId, Channel, ChannelId, CommandId, Event, GuildId, Member, Role,
+	RoleId, UserId,
 };
 use futures::{Stream, StreamExt};
 use log::{debug, info};
@@ -17,10 +18,12 @@ pub struct Guild<S> {
 	name: String,
 	available: bool,
 	channels: HashMap<ChannelId, Channel>,
+	threads: HashMap<ChannelId, Channel>,
 	roles: HashMap<RoleId, Role>,
 	member_count: usize,
 	members: HashMap<UserId, Member>,
-	commands: HashMap<String, ApplicationCommand>,
+	/// By id, a chat input and a context menu command can share a name
+	commands: HashMap<CommandId, ApplicationCommand>,
 	stream: S,
 	client: Client,
 }
@@ -33,6 +36,7 @@ impl<S> Guild<S> {
 	fn update(&mut self, guild: discord_types::Guild) {
 		self.name = guild.name;
 		self.channels = guild.channels.into_iter().map(|c| (c.id, c)).collect();
+		self.threads = guild.threads.into_iter().map(|c| (c.id, c)).collect();
 		self.roles = guild.roles.into_iter().map(|r| (r.id, r)).collect();
 		for (id, member) in guild
 			.members
@@ -50,9 +54,10 @@ impl<S> Guild<S> {
 		}
 
 		info!(
-			"Loaded guild '{}' ({} channels, {} roles, {}/{} members, {} commands)",
+			"Loaded guild '{}' ({} channels, {} threads, {} roles, {}/{} members, {} commands)",
 			self.name,
 			self.channels.len(),
+			self.threads.len(),
 			self.roles.len(),
 			member_count,
 			actual_member_count,
@@ -63,10 +68,10 @@ impl<S> Guild<S> {
 	async fn load_commands(&mut self) -> Result<(), Error> {
 		self.commands = self
 			.client
-			.commands(self.application_id, self.id)
+			.get_guild_commands(self.application_id, self.id)
 			.await?
 			.into_iter()
-			.map(|c| (c.name.clone(), c))
+			.map(|c| (c.id, c))
 			.collect();
 		Ok(())
 	}
@@ -103,8 +108,19 @@ impl<S> Guild<S> {
 		self.channels.values()
 	}
 
+	/// Channel or active thread
 	pub fn channel<T: Into<ChannelId>>(&self, id: T) -> Option<&Channel> {
-		self.channels.get(&id.into())
+		let id = id.into();
+		self.channels.get(&id).or_else(|| self.threads.get(&id))
+	}
+
+	/// Active threads the bot can see
+	pub fn threads(&self) -> impl Iterator<Item = &Channel> {
+		self.threads.values()
+	}
+
+	pub fn thread<T: Into<ChannelId>>(&self, id: T) -> Option<&Channel> {
+		self.threads.get(&id.into())
 	}
 
 	pub fn roles(&self) -> impl Iterator<Item = &Role> {
@@ -137,8 +153,32 @@ impl<S> Guild<S> {
 			.unwrap_or(0)
 	}
 
-	pub fn command(&self, name: &str) -> Option<&ApplicationCommand> {
-		self.commands.get(name)
+	pub fn commands(&self) -> impl Iterator<Item = &ApplicationCommand> {
+		self.commands.values()
+	}
+
+	/// Names are only unique per command type
+	pub fn command(&self, name: &str, command_type: CommandType) -> Option<&ApplicationCommand> {
+		self.commands
+			.values()
+			.find(|c| c.name == name && kind(c) == command_type as u64)
+	}
+
+	/// Bring the guild's commands in line with `registry` and reload them if anything changed.
+	/// Use `Client::sync_guild_commands` with `dry_run` to only see the changes
+	pub async fn sync_commands(
+		&mut self,
+		registry: &CommandRegistry,
+	) -> Result<Vec<CommandChange>, Error> {
+		let changes = self
+			.client
+			.sync_guild_commands(self.application_id, self.id, registry)
+			.send()
+			.await?;
+		if !changes.is_empty() {
+			self.load_commands().await?;
+		}
+		Ok(changes)
 	}
 }
 
@@ -160,6 +200,7 @@ where
 			name: gc.guild.name.clone(),
 			available: false,
 			channels: HashMap::new(),
+			threads: HashMap::new(),
 			roles: HashMap::new(),
 			member_count: 0,
 			members: HashMap::new(),
@@ -257,8 +298,48 @@ where
 				}
 				ChannelDelete(cd) => {
 					self.channels.remove(&cd.channel.id);
+					self.threads
+						.retain(|_, t| t.parent_id != Some(cd.channel.id));
 					ChannelDelete(cd)
 				}
+				ThreadCreate(tc) => {
+					let thread = tc.channel.clone();
+					self.threads.insert(thread.id, thread);
+					ThreadCreate(tc)
+				}
+				ThreadUpdate(tu) => {
+					let thread = tu.channel.clone();
+					self.threads.insert(thread.id, thread);
+					ThreadUpdate(tu)
+				}
+				ThreadDelete(td) => {
+					self.threads.remove(&td.id);
+					ThreadDelete(td)
+				}
+				ThreadListSync(ls) => {
+					// Sent when gaining access to channels, replaces all threads of
+					// the synced channels, or of the entire guild if none are specified
+					match &ls.channel_ids {
+						Some(ids) => self
+							.threads
+							.retain(|_, t| !t.parent_id.map(|p| ids.contains(&p)).unwrap_or(false)),
+						None => self.threads.clear(),
+					}
+					for thread in &ls.threads {
+						self.threads.insert(thread.id, thread.clone());
+					}
+					ThreadListSync(ls)
+				}
+				ThreadMembersUpdate(mu) => {
+					if let Some(thread) = self.threads.get_mut(&mu.id) {
+						thread.member_count = Some(mu.member_count.into());
+					}
+					// Private threads are no longer visible once the bot is removed
+					if mu.removed_member_ids.contains(&self.user_id) {
+						self.threads.remove(&mu.id);
+					}
+					ThreadMembersUpdate(mu)
+				}
 				/*MessageReactionAdd(ra) => Event::ReactionAdd(
 					ra.user_id.into(),
 					ra.channel_id.into(),
@@ -282,16 +363,16 @@ where
 				),*/
 				ApplicationCommandCreate(cc) => {
 					let command = cc.command.clone();
-					self.commands.insert(command.name.clone(), command);
+					self.commands.insert(command.id, command);
 					ApplicationCommandCreate(cc)
 				}
 				ApplicationCommandUpdate(cu) => {
 					let command = cu.command.clone();
-					self.commands.insert(command.name.clone(), command);
+					self.commands.insert(command.id, command);
 					ApplicationCommandUpdate(cu)
 				}
 				ApplicationCommandDelete(cd) => {
-					self.commands.remove(&cd.command.name);
+					self.commands.remove(&cd.command.id);
 					ApplicationCommandDelete(cd)
 				}
 				e @ InteractionCreate(_) => e,
@@ -313,3 +394,71 @@ where
 		}
 	}
 }
+
+/// Type of a command, as Discord sends it. Chat input commands may omit it
+fn kind(command: &ApplicationCommand) -> u64 {
+	serde_json::to_value(command)
+		.ok()
+		.and_then(|c| c["type"].as_u64())
+		.unwrap_or(CommandType::ChatInput as u64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::stream;
+	use serde::de::DeserializeOwned;
+	use serde_json::json;
+
+	fn id<T: DeserializeOwned>(id: &str) -> T {
+		serde_json::from_value(json!(id)).unwrap()
+	}
+
+	#[tokio::test]
+	async fn thread_cache() {
+		let thread = json!({
+			"id": "1130000000000000001",
+			"type": 11,
+			"guild_id": "1130000000000000000",
+			"parent_id": "1130000000000000002",
+			"owner_id": "1130000000000000003",
+			"name": "Thread",
+			"last_message_id": null,
+			"message_count": 0,
+			"member_count": 1,
+			"rate_limit_per_user": 0,
+			"flags": 0,
+			"thread_metadata": {
+				"archived": false,
+				"auto_archive_duration": 1440,
+				"archive_timestamp": "2023-07-17T12:00:00.000000+00:00",
+				"locked": false,
+			},
+			"total_message_sent": 0,
+			"newly_created": true,
+		});
+		let thread_create = serde_json::from_value(thread).unwrap();
+		let events = vec![GatewayEvent::Event(Event::ThreadCreate(thread_create))];
+
+		let mut guild = Guild {
+			id: id("1130000000000000000"),
+			user_id: id("1130000000000000004"),
+			application_id: id("1130000000000000005"),
+			name: "Guild".into(),
+			available: true,
+			channels: HashMap::new(),
+			threads: HashMap::new(),
+			roles: HashMap::new(),
+			member_count: 0,
+			members: HashMap::new(),
+			commands: HashMap::new(),
+			stream: stream::iter(events),
+			client: Client::new("token", None).unwrap(),
+		};
+		assert!(guild.next().await.is_some());
+
+		let thread_id: ChannelId = id("1130000000000000001");
+		assert_eq!(guild.channel(thread_id).map(|c| c.id), Some(thread_id));
+		assert_eq!(guild.threads().count(), 1);
+	}
+}
@@ -1,15 +1,98 @@
-pub use crate::client::{ButtonComponent, RowComponent};
+pub use self::command::{
+	Command, CommandArgs, CommandOptions, Focused, FromOption, OptionSpec, OptionType, ParseError,
+	ResolvedAttachment,
+};
+pub use self::modal::ModalSubmit;
+pub use self::router::{Context, HandlerError, Router};
+pub use crate::client::{
+	ButtonComponent, ModalBuilder, RowComponent, TextInputComponent, TextInputStyle,
+};
 use crate::client::{Client, Error};
 use crate::guild::Guild;
+#[cfg(feature = "derive")]
+pub use discord_async_derive::Command;
 pub use discord_types::{AllowedMentions, Interaction};
 use discord_types::{Embed, InteractionId};
+use futures::future::BoxFuture;
 use log::warn;
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::Value;
 use std::borrow::Cow;
 use std::future::Future;
+use std::ops::Deref;
 use tokio::task::JoinHandle;
 
+mod command;
+mod modal;
+mod router;
+
+/// Interaction along with the JSON it was received as, whose `data` has fields that
+/// `Interaction` doesn't, like the values of options and text inputs
+#[derive(Debug)]
+pub struct RawInteraction {
+	interaction: Interaction,
+	raw: Value,
+}
+
+impl RawInteraction {
+	pub fn new(raw: Value) -> Result<Self, serde_json::Error> {
+		let interaction = Interaction::deserialize(&raw)?;
+		Ok(Self { interaction, raw })
+	}
+
+	pub fn interaction(&self) -> &Interaction {
+		&self.interaction
+	}
+
+	pub fn into_interaction(self) -> Interaction {
+		self.interaction
+	}
+
+	/// Interaction type, e.g. 2 for application commands
+	pub fn kind(&self) -> u64 {
+		self.raw["type"].as_u64().unwrap_or(0)
+	}
+
+	/// Raw `data` of the interaction
+	pub fn data(&self) -> &Value {
+		&self.raw["data"]
+	}
+
+	pub fn raw(&self) -> &Value {
+		&self.raw
+	}
+}
+
+impl Deref for RawInteraction {
+	type Target = Interaction;
+
+	fn deref(&self) -> &Interaction {
+		&self.interaction
+	}
+}
+
+impl<'de> Deserialize<'de> for RawInteraction {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Self::new(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+	}
+}
+
 pub trait CanRespond {
 	fn respond<S>(&self, guild: &Guild<S>) -> ResponseBuilder;
+
+	/// Response to an autocomplete interaction
+	fn autocomplete<S>(&self, guild: &Guild<S>) -> AutocompleteBuilder {
+		AutocompleteBuilder::new(self.respond(guild))
+	}
+
+	/// Show a modal, the submitted values arrive as a new interaction
+	fn modal<S>(
+		&self,
+		guild: &Guild<S>,
+		modal: ModalBuilder,
+	) -> BoxFuture<'static, Result<(), Error>> {
+		send_modal(self.respond(guild), modal)
+	}
 }
 
 impl CanRespond for Interaction {
@@ -18,6 +101,20 @@ impl CanRespond for Interaction {
 	}
 }
 
+/// Uses the interaction and client of `response`
+fn send_modal(
+	response: ResponseBuilder,
+	modal: ModalBuilder,
+) -> BoxFuture<'static, Result<(), Error>> {
+	let ResponseBuilder {
+		interaction_id,
+		token,
+		client,
+		..
+	} = response;
+	Box::pin(async move { client.modal_response(interaction_id, &token, &modal).await })
+}
+
 #[derive(Clone)]
 pub struct ResponseBuilder {
 	interaction_id: InteractionId,
@@ -140,3 +237,87 @@ impl ResponseBuilder {
 		})
 	}
 }
+
+/// Up to 25 suggestions for the focused option, sending more fails
+#[derive(Clone)]
+pub struct AutocompleteBuilder {
+	interaction_id: InteractionId,
+	token: String,
+	client: Client,
+	choices: Vec<(Cow<'static, str>, Value)>,
+}
+
+impl AutocompleteBuilder {
+	/// Uses the interaction and client of `response`
+	fn new(response: ResponseBuilder) -> Self {
+		AutocompleteBuilder {
+			interaction_id: response.interaction_id,
+			token: response.token,
+			client: response.client,
+			choices: Vec::new(),
+		}
+	}
+
+	pub fn choice<T: Into<Cow<'static, str>>, V: Into<Value>>(mut self, name: T, value: V) -> Self {
+		self.choices.push((name.into(), value.into()));
+		self
+	}
+
+	pub fn choices<I, T, V>(mut self, choices: I) -> Self
+	where
+		I: IntoIterator<Item = (T, V)>,
+		T: Into<Cow<'static, str>>,
+		V: Into<Value>,
+	{
+		self.choices.extend(
+			choices
+				.into_iter()
+				.map(|(name, value)| (name.into(), value.into())),
+		);
+		self
+	}
+
+	pub fn send(self) -> impl Future<Output = Result<(), Error>> {
+		let Self {
+			interaction_id,
+			token,
+			client,
+			choices,
+		} = self;
+
+		async move {
+			client
+				.autocomplete_response(interaction_id, &token, choices)
+				.await
+		}
+	}
+
+	pub fn spawn(self) -> JoinHandle<Result<(), Error>> {
+		let task = self.send();
+		tokio::spawn(async move {
+			let res = task.await;
+			if let Err(e) = &res {
+				warn!("Unable to send autocomplete response: {}", e);
+			}
+			res
+		})
+	}
+}
+
+/// Minimal interaction of `kind` with `data`, for tests
+#[cfg(test)]
+pub(crate) fn test_interaction(kind: u8, data: Value) -> RawInteraction {
+	RawInteraction::new(serde_json::json!({
+		"id": "1",
+		"application_id": "2",
+		"type": kind,
+		"data": data,
+		"guild_id": "3",
+		"channel_id": "4",
+		"token": "token",
+		"version": 1,
+		"app_permissions": "0",
+		"locale": "en-US",
+	}))
+	.unwrap()
+}
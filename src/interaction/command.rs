@@ -0,0 +1,617 @@
+//! Typed arguments of application commands, usually implemented with `#[derive(Command)]`
+
+use super::RawInteraction;
+use crate::client::NewCommand;
+use discord_types::{
+	ApplicationCommandOption, Channel, ChannelId, Member, Role, RoleId, User, UserId,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_repr::Serialize_repr;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize_repr)]
+#[repr(u8)]
+pub enum OptionType {
+	SubCommand = 1,
+	SubCommandGroup = 2,
+	String = 3,
+	Integer = 4,
+	Boolean = 5,
+	User = 6,
+	Channel = 7,
+	Role = 8,
+	Mentionable = 9,
+	Number = 10,
+	Attachment = 11,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+	/// The interaction has no command data
+	NoData,
+	MissingOption(&'static str),
+	/// The value has the wrong type, or isn't in the resolved data
+	InvalidOption(&'static str),
+	MissingSubcommand,
+	UnknownSubcommand(String),
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ParseError::NoData => write!(f, "Interaction has no command data"),
+			ParseError::MissingOption(name) => write!(f, "Missing option '{}'", name),
+			ParseError::InvalidOption(name) => write!(f, "Invalid value for option '{}'", name),
+			ParseError::MissingSubcommand => write!(f, "Missing subcommand"),
+			ParseError::UnknownSubcommand(name) => write!(f, "Unknown subcommand '{}'", name),
+		}
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+/// Options of a command, or of one of its subcommands or groups
+pub trait CommandArgs: Sized {
+	/// Type of the option when nested in another command, `SubCommand` for a struct
+	/// of options and `SubCommandGroup` for an enum of subcommands
+	const NESTED_TYPE: OptionType;
+
+	fn options() -> Vec<OptionSpec>;
+
+	fn from_options(options: CommandOptions<'_>) -> Result<Self, ParseError>;
+}
+
+/// Chat input command with typed arguments
+pub trait Command: CommandArgs {
+	const NAME: &'static str;
+	const DESCRIPTION: &'static str;
+
+	fn new_command() -> NewCommand {
+		NewCommand::chat_input(Self::NAME, Self::DESCRIPTION).args::<Self>()
+	}
+
+	/// Options as accepted by `Client::create_command`
+	fn command_options() -> Result<Vec<ApplicationCommandOption>, serde_json::Error> {
+		Self::options()
+			.iter()
+			.map(|o| serde_json::from_value(serde_json::to_value(o)?))
+			.collect()
+	}
+
+	fn parse(interaction: &RawInteraction) -> Result<Self, ParseError> {
+		Self::parse_data(interaction.data())
+	}
+
+	/// Parse the `data` of an interaction
+	fn parse_data(data: &Value) -> Result<Self, ParseError> {
+		if !data.is_object() {
+			return Err(ParseError::NoData);
+		}
+		Self::from_options(CommandOptions::new(data))
+	}
+}
+
+/// Option of a command, as it is registered
+#[derive(Clone, Debug, Serialize)]
+pub struct OptionSpec {
+	#[serde(rename = "type")]
+	option_type: OptionType,
+	name: &'static str,
+	description: &'static str,
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	required: bool,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	choices: Vec<Choice>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	options: Vec<OptionSpec>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	channel_types: Vec<u8>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	min_value: Option<Value>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	max_value: Option<Value>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	min_length: Option<u16>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	max_length: Option<u16>,
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	autocomplete: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Choice {
+	name: &'static str,
+	value: Value,
+}
+
+impl OptionSpec {
+	pub fn new<T: FromOption>(name: &'static str, description: &'static str) -> Self {
+		Self::with_type(T::OPTION_TYPE, name, description)
+	}
+
+	/// Subcommand or group with the options of `T`
+	pub fn nested<T: CommandArgs>(name: &'static str, description: &'static str) -> Self {
+		let mut spec = Self::with_type(T::NESTED_TYPE, name, description);
+		spec.options = T::options();
+		spec
+	}
+
+	/// Subcommand without options
+	pub fn subcommand(name: &'static str, description: &'static str) -> Self {
+		Self::with_type(OptionType::SubCommand, name, description)
+	}
+
+	fn with_type(option_type: OptionType, name: &'static str, description: &'static str) -> Self {
+		Self {
+			option_type,
+			name,
+			description,
+			required: false,
+			choices: Vec::new(),
+			options: Vec::new(),
+			channel_types: Vec::new(),
+			min_value: None,
+			max_value: None,
+			min_length: None,
+			max_length: None,
+			autocomplete: false,
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		self.name
+	}
+
+	pub fn required(mut self, required: bool) -> Self {
+		self.required = required;
+		self
+	}
+
+	/// Restrict the value to one of the choices
+	pub fn choice<T: Into<Value>>(mut self, name: &'static str, value: T) -> Self {
+		self.choices.push(Choice {
+			name,
+			value: value.into(),
+		});
+		self
+	}
+
+	/// Only for channel options, see `ChannelType`
+	pub fn channel_types<I: IntoIterator<Item = u8>>(mut self, channel_types: I) -> Self {
+		self.channel_types.extend(channel_types);
+		self
+	}
+
+	/// Only for integer and number options
+	pub fn min_value<T: Into<Value>>(mut self, min: T) -> Self {
+		self.min_value = Some(min.into());
+		self
+	}
+
+	pub fn max_value<T: Into<Value>>(mut self, max: T) -> Self {
+		self.max_value = Some(max.into());
+		self
+	}
+
+	/// Only for string options
+	pub fn min_length(mut self, min: u16) -> Self {
+		self.min_length = Some(min);
+		self
+	}
+
+	pub fn max_length(mut self, max: u16) -> Self {
+		self.max_length = Some(max);
+		self
+	}
+
+	/// Suggest values with autocomplete interactions, can't be combined with choices
+	pub fn autocomplete(mut self, autocomplete: bool) -> Self {
+		self.autocomplete = autocomplete;
+		self
+	}
+}
+
+/// Option values of an interaction, along with the users, roles, etc. they refer to
+#[derive(Clone, Copy, Debug)]
+pub struct CommandOptions<'a> {
+	options: &'a [Value],
+	resolved: &'a Value,
+}
+
+impl<'a> CommandOptions<'a> {
+	/// From the `data` of an interaction
+	pub fn new(data: &'a Value) -> Self {
+		Self {
+			options: array(&data["options"]),
+			resolved: &data["resolved"],
+		}
+	}
+
+	fn value(&self, name: &str) -> Option<&'a Value> {
+		self.options
+			.iter()
+			.find(|o| o["name"] == name)
+			.map(|o| &o["value"])
+	}
+
+	pub fn get<T: FromOption>(&self, name: &'static str) -> Result<Option<T>, ParseError> {
+		match self.value(name) {
+			Some(value) => T::from_option(value, self.resolved)
+				.map(Some)
+				.ok_or(ParseError::InvalidOption(name)),
+			None => Ok(None),
+		}
+	}
+
+	pub fn require<T: FromOption>(&self, name: &'static str) -> Result<T, ParseError> {
+		self.get(name)?.ok_or(ParseError::MissingOption(name))
+	}
+
+	/// Option the user is typing in, for autocomplete interactions
+	pub fn focused(&self) -> Option<Focused<'a>> {
+		let option = self.options.iter().find(|o| o["focused"] == true)?;
+		Some(Focused {
+			name: option["name"].as_str()?,
+			value: &option["value"],
+		})
+	}
+
+	/// Name and options of the chosen subcommand or group
+	pub fn subcommand(&self) -> Result<(&'a str, CommandOptions<'a>), ParseError> {
+		let option = self
+			.options
+			.iter()
+			.find(|o| matches!(o["type"].as_u64(), Some(1 | 2)))
+			.ok_or(ParseError::MissingSubcommand)?;
+		let name = option["name"]
+			.as_str()
+			.ok_or(ParseError::MissingSubcommand)?;
+		let options = CommandOptions {
+			options: array(&option["options"]),
+			resolved: self.resolved,
+		};
+		Ok((name, options))
+	}
+}
+
+/// Focused option of an autocomplete interaction
+#[derive(Clone, Copy, Debug)]
+pub struct Focused<'a> {
+	pub name: &'a str,
+	/// What the user has typed so far, not necessarily a valid value of the option
+	pub value: &'a Value,
+}
+
+impl<'a> Focused<'a> {
+	/// The value as typed, e.g. `"12"` for a number
+	pub fn text(&self) -> String {
+		match self.value {
+			Value::String(text) => text.clone(),
+			Value::Null => String::new(),
+			value => value.to_string(),
+		}
+	}
+}
+
+fn array(value: &Value) -> &[Value] {
+	value.as_array().map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// Type of a command argument
+pub trait FromOption: Sized {
+	const OPTION_TYPE: OptionType;
+
+	/// `resolved` holds the objects that ids in the value refer to
+	fn from_option(value: &Value, resolved: &Value) -> Option<Self>;
+}
+
+impl FromOption for String {
+	const OPTION_TYPE: OptionType = OptionType::String;
+
+	fn from_option(value: &Value, _: &Value) -> Option<Self> {
+		value.as_str().map(str::to_owned)
+	}
+}
+
+macro_rules! integer_option {
+	($($t:ty),*) => {
+		$(
+			impl FromOption for $t {
+				const OPTION_TYPE: OptionType = OptionType::Integer;
+
+				fn from_option(value: &Value, _: &Value) -> Option<Self> {
+					value.as_i64()?.try_into().ok()
+				}
+			}
+		)*
+	};
+}
+
+integer_option!(i64, i32, i16, u64, u32, u16, u8);
+
+impl FromOption for f64 {
+	const OPTION_TYPE: OptionType = OptionType::Number;
+
+	fn from_option(value: &Value, _: &Value) -> Option<Self> {
+		value.as_f64()
+	}
+}
+
+impl FromOption for bool {
+	const OPTION_TYPE: OptionType = OptionType::Boolean;
+
+	fn from_option(value: &Value, _: &Value) -> Option<Self> {
+		value.as_bool()
+	}
+}
+
+macro_rules! id_option {
+	($($t:ty => $o:ident),*) => {
+		$(
+			impl FromOption for $t {
+				const OPTION_TYPE: OptionType = OptionType::$o;
+
+				fn from_option(value: &Value, _: &Value) -> Option<Self> {
+					Deserialize::deserialize(value).ok()
+				}
+			}
+		)*
+	};
+}
+
+id_option!(UserId => User, RoleId => Role, ChannelId => Channel);
+
+/// Object that the id in `value` refers to
+fn resolve<T: for<'de> Deserialize<'de>>(value: &Value, resolved: &Value, kind: &str) -> Option<T> {
+	Deserialize::deserialize(resolved.get(kind)?.get(value.as_str()?)?).ok()
+}
+
+impl FromOption for User {
+	const OPTION_TYPE: OptionType = OptionType::User;
+
+	fn from_option(value: &Value, resolved: &Value) -> Option<Self> {
+		resolve(value, resolved, "users")
+	}
+}
+
+/// Only available in guilds
+impl FromOption for Member {
+	const OPTION_TYPE: OptionType = OptionType::User;
+
+	fn from_option(value: &Value, resolved: &Value) -> Option<Self> {
+		// Resolved members don't include their user
+		let id = value.as_str()?;
+		let mut member = resolved.get("members")?.get(id)?.clone();
+		member["user"] = resolved.get("users")?.get(id)?.clone();
+		Deserialize::deserialize(member).ok()
+	}
+}
+
+impl FromOption for Role {
+	const OPTION_TYPE: OptionType = OptionType::Role;
+
+	fn from_option(value: &Value, resolved: &Value) -> Option<Self> {
+		resolve(value, resolved, "roles")
+	}
+}
+
+/// Partial channel, with only its id, name, type, permissions and thread fields
+impl FromOption for Channel {
+	const OPTION_TYPE: OptionType = OptionType::Channel;
+
+	fn from_option(value: &Value, resolved: &Value) -> Option<Self> {
+		resolve(value, resolved, "channels")
+	}
+}
+
+/// File uploaded as an attachment option
+#[derive(Clone, Debug, Deserialize)]
+pub struct ResolvedAttachment {
+	pub id: String,
+	pub filename: String,
+	pub size: u64,
+	pub url: String,
+	pub proxy_url: String,
+	pub content_type: Option<String>,
+	pub description: Option<String>,
+}
+
+impl FromOption for ResolvedAttachment {
+	const OPTION_TYPE: OptionType = OptionType::Attachment;
+
+	fn from_option(value: &Value, resolved: &Value) -> Option<Self> {
+		resolve(value, resolved, "attachments")
+	}
+}
+
+/// Errors of `#[derive(Command)]`, checked by doc tests
+///
+/// ```
+/// use discord_async::interaction::Command;
+///
+/// /// Roll some dice
+/// #[derive(Command)]
+/// struct Roll {
+///     /// Number of sides
+///     sides: i64,
+/// }
+/// ```
+///
+/// Missing description:
+/// ```compile_fail
+/// use discord_async::interaction::Command;
+///
+/// /// Roll some dice
+/// #[derive(Command)]
+/// struct Roll {
+///     sides: i64,
+/// }
+/// ```
+///
+/// Uppercase name:
+/// ```compile_fail
+/// use discord_async::interaction::Command;
+///
+/// /// Roll some dice
+/// #[derive(Command)]
+/// #[command(name = "Roll")]
+/// struct Roll;
+/// ```
+///
+/// Name with a space:
+/// ```compile_fail
+/// use discord_async::interaction::Command;
+///
+/// /// Roll some dice
+/// #[derive(Command)]
+/// #[command(name = "roll dice")]
+/// struct Roll;
+/// ```
+///
+/// Variant with named fields:
+/// ```compile_fail
+/// use discord_async::interaction::Command;
+///
+/// /// Manage the configuration
+/// #[derive(Command)]
+/// enum Config {
+///     /// Set a value
+///     Set { value: String },
+/// }
+/// ```
+///
+/// Tuple struct:
+/// ```compile_fail
+/// use discord_async::interaction::Command;
+///
+/// /// Roll some dice
+/// #[derive(Command)]
+/// struct Roll(i64);
+/// ```
+#[cfg(all(doctest, feature = "derive"))]
+pub struct DeriveErrors;
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+	use super::*;
+	use crate::interaction::test_interaction;
+	use serde_json::json;
+
+	/// Roll some dice
+	#[derive(Debug, PartialEq, crate::interaction::Command)]
+	struct Roll {
+		/// Number of sides
+		#[option(min = 2, max = 100)]
+		sides: i64,
+		/// How many dice to roll
+		count: Option<u8>,
+		/// Name of a saved roll
+		#[option(autocomplete)]
+		preset: Option<String>,
+	}
+
+	/// Manage the configuration
+	#[derive(Debug, PartialEq, crate::interaction::Command)]
+	#[command(name = "config")]
+	enum Config {
+		/// Show the configuration
+		Show,
+		/// Set the log channel
+		LogChannel(LogChannel),
+	}
+
+	#[derive(Debug, PartialEq, crate::interaction::Command)]
+	#[command(description = "Set the log channel")]
+	struct LogChannel {
+		#[option(description = "Channel to log to", channel_types = [0, 5])]
+		channel: ChannelId,
+	}
+
+	#[test]
+	fn derive_options() {
+		assert_eq!(Roll::NAME, "roll");
+		let options = serde_json::to_value(Roll::options()).unwrap();
+		assert_eq!(
+			options,
+			json!([
+				{
+					"type": 4,
+					"name": "sides",
+					"description": "Number of sides",
+					"required": true,
+					"min_value": 2,
+					"max_value": 100,
+				},
+				{"type": 4, "name": "count", "description": "How many dice to roll"},
+				{
+					"type": 3,
+					"name": "preset",
+					"description": "Name of a saved roll",
+					"autocomplete": true,
+				},
+			])
+		);
+
+		let options = serde_json::to_value(Config::options()).unwrap();
+		assert_eq!(options[0]["type"], 1);
+		assert_eq!(options[1]["name"], "log-channel");
+		assert_eq!(options[1]["options"][0]["channel_types"], json!([0, 5]));
+	}
+
+	#[test]
+	fn derive_parse() {
+		let data = json!({
+			"name": "roll",
+			"options": [{"name": "sides", "type": 4, "value": 6}],
+		});
+		assert_eq!(
+			Roll::parse_data(&data),
+			Ok(Roll {
+				sides: 6,
+				count: None,
+				preset: None,
+			})
+		);
+		assert_eq!(
+			Roll::parse_data(&json!({"name": "roll"})),
+			Err(ParseError::MissingOption("sides"))
+		);
+
+		let data = json!({
+			"name": "config",
+			"options": [{
+				"name": "log-channel",
+				"type": 1,
+				"options": [{"name": "channel", "type": 7, "value": "1234"}],
+			}],
+		});
+		assert!(matches!(
+			Config::parse_data(&data),
+			Ok(Config::LogChannel(_))
+		));
+	}
+
+	#[test]
+	fn parse_interaction() {
+		let data = json!({
+			"id": "5",
+			"name": "roll",
+			"type": 1,
+			"options": [
+				{"name": "sides", "type": 4, "value": 20},
+				{"name": "count", "type": 4, "value": 2},
+			],
+		});
+		let interaction = test_interaction(2, data);
+		assert_eq!(interaction.kind(), 2);
+		assert_eq!(
+			Roll::parse(&interaction),
+			Ok(Roll {
+				sides: 20,
+				count: Some(2),
+				preset: None,
+			})
+		);
+	}
+}
@@ -0,0 +1,108 @@
+use super::{ParseError, RawInteraction};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Values of the text inputs of a submitted modal, by their `custom_id`
+#[derive(Clone, Debug)]
+pub struct ModalSubmit {
+	custom_id: String,
+	values: HashMap<String, String>,
+}
+
+impl ModalSubmit {
+	pub fn from_interaction(interaction: &RawInteraction) -> Result<Self, ParseError> {
+		Self::from_data(interaction.data())
+	}
+
+	/// From the `data` of a modal submit interaction
+	pub fn from_data(data: &Value) -> Result<Self, ParseError> {
+		let custom_id = data["custom_id"].as_str().ok_or(ParseError::NoData)?;
+		let values = data["components"]
+			.as_array()
+			.into_iter()
+			.flatten()
+			.flat_map(|row| row["components"].as_array().into_iter().flatten())
+			.filter_map(|input| {
+				let id = input["custom_id"].as_str()?;
+				let value = input["value"].as_str().unwrap_or_default();
+				Some((id.to_owned(), value.to_owned()))
+			})
+			.collect();
+
+		Ok(Self {
+			custom_id: custom_id.to_owned(),
+			values,
+		})
+	}
+
+	/// `custom_id` of the modal
+	pub fn custom_id(&self) -> &str {
+		&self.custom_id
+	}
+
+	/// Raw value of a text input, empty if an optional input was left blank
+	pub fn value(&self, custom_id: &str) -> Option<&str> {
+		self.values.get(custom_id).map(String::as_str)
+	}
+
+	/// Parsed value of a text input, `None` if it is missing or blank
+	pub fn get<T: FromStr>(&self, custom_id: &'static str) -> Result<Option<T>, ParseError> {
+		match self.value(custom_id).map(str::trim) {
+			Some(value) if !value.is_empty() => value
+				.parse()
+				.map(Some)
+				.map_err(|_| ParseError::InvalidOption(custom_id)),
+			_ => Ok(None),
+		}
+	}
+
+	pub fn require<T: FromStr>(&self, custom_id: &'static str) -> Result<T, ParseError> {
+		self.get(custom_id)?
+			.ok_or(ParseError::MissingOption(custom_id))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::interaction::test_interaction;
+	use serde_json::json;
+
+	#[test]
+	fn submitted_values() {
+		let data = json!({
+			"custom_id": "report:1234",
+			"components": [
+				{"type": 1, "components": [{"type": 4, "custom_id": "reason", "value": "Spam"}]},
+				{"type": 1, "components": [{"type": 4, "custom_id": "severity", "value": " 3 "}]},
+				{"type": 1, "components": [{"type": 4, "custom_id": "notes", "value": ""}]},
+			],
+		});
+		let submit = ModalSubmit::from_data(&data).unwrap();
+		assert_eq!(submit.custom_id(), "report:1234");
+		assert_eq!(submit.require::<String>("reason").unwrap(), "Spam");
+		assert_eq!(submit.require::<u8>("severity"), Ok(3));
+		assert_eq!(submit.get::<String>("notes"), Ok(None));
+		assert_eq!(
+			submit.get::<u8>("reason"),
+			Err(ParseError::InvalidOption("reason"))
+		);
+	}
+
+	#[test]
+	fn from_interaction() {
+		let data = json!({
+			"custom_id": "report:1234",
+			"components": [{
+				"type": 1,
+				"components": [{"type": 4, "custom_id": "reason", "value": "Spam"}],
+			}],
+		});
+		let interaction = test_interaction(5, data);
+		assert_eq!(interaction.kind(), 5);
+		let submit = ModalSubmit::from_interaction(&interaction).unwrap();
+		assert_eq!(submit.custom_id(), "report:1234");
+		assert_eq!(submit.value("reason"), Some("Spam"));
+	}
+}
@@ -0,0 +1,501 @@
+use super::{
+	send_modal, AutocompleteBuilder, Command, CommandOptions, Focused, ModalSubmit, ParseError,
+	RawInteraction, ResponseBuilder,
+};
+use crate::client::{Client, CommandType, Error, ModalBuilder};
+use crate::guild::Guild;
+use discord_types::{ApplicationId, GuildId, Interaction};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use log::{debug, warn};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+/// Error returned by a handler, any error can be converted with `?`
+pub type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+
+type Handler = Arc<dyn Fn(Context) -> BoxFuture<'static, Result<(), HandlerError>> + Send + Sync>;
+type ErrorHandler = Arc<dyn Fn(&str, &HandlerError) + Send + Sync>;
+
+const APPLICATION_COMMAND: u64 = 2;
+const MESSAGE_COMPONENT: u64 = 3;
+const AUTOCOMPLETE: u64 = 4;
+const MODAL_SUBMIT: u64 = 5;
+
+/// Interaction along with the guild it was received in, passed to handlers
+pub struct Context {
+	client: Client,
+	guild_id: GuildId,
+	application_id: ApplicationId,
+	interaction: RawInteraction,
+	params: HashMap<String, String>,
+}
+
+impl Context {
+	pub fn client(&self) -> &Client {
+		&self.client
+	}
+
+	pub fn guild_id(&self) -> GuildId {
+		self.guild_id
+	}
+
+	pub fn application_id(&self) -> ApplicationId {
+		self.application_id
+	}
+
+	pub fn interaction(&self) -> &Interaction {
+		self.interaction.interaction()
+	}
+
+	pub fn raw_interaction(&self) -> &RawInteraction {
+		&self.interaction
+	}
+
+	/// Raw `data` of the interaction
+	pub fn data(&self) -> &Value {
+		self.interaction.data()
+	}
+
+	pub fn respond(&self) -> ResponseBuilder {
+		ResponseBuilder::new(self.interaction(), self.client.clone())
+	}
+
+	/// Response to an autocomplete interaction
+	pub fn autocomplete(&self) -> AutocompleteBuilder {
+		AutocompleteBuilder::new(self.respond())
+	}
+
+	/// Show a modal, handle its submission with `Router::modal`
+	pub fn modal(&self, modal: ModalBuilder) -> BoxFuture<'static, Result<(), Error>> {
+		send_modal(self.respond(), modal)
+	}
+
+	/// Submitted values, for modal submit interactions
+	pub fn modal_submit(&self) -> Result<ModalSubmit, ParseError> {
+		ModalSubmit::from_interaction(&self.interaction)
+	}
+
+	/// Option the user is typing in, for autocomplete interactions
+	pub fn focused(&self) -> Option<Focused<'_>> {
+		self.options().focused()
+	}
+
+	/// Parameter captured from the `custom_id`, e.g. `id` of `ticket:close:{id}`
+	pub fn param(&self, name: &str) -> Option<&str> {
+		self.params.get(name).map(String::as_str)
+	}
+
+	pub fn custom_id(&self) -> Option<&str> {
+		self.data()["custom_id"].as_str()
+	}
+
+	/// Id of the user or message a context menu command was used on
+	pub fn target_id(&self) -> Option<&str> {
+		self.data()["target_id"].as_str()
+	}
+
+	/// Options of the invoked subcommand
+	pub fn options(&self) -> CommandOptions<'_> {
+		let mut options = CommandOptions::new(self.data());
+		while let Ok((_, nested)) = options.subcommand() {
+			options = nested;
+		}
+		options
+	}
+
+	/// Parse the whole command, including any subcommands
+	pub fn args<T: Command>(&self) -> Result<T, ParseError> {
+		T::parse(&self.interaction)
+	}
+}
+
+#[derive(Clone)]
+enum Route {
+	Command(CommandType, Vec<String>),
+	/// Command path and the name of the focused option
+	Autocomplete(Vec<String>, String),
+	Component(Vec<Segment>),
+	Modal(Vec<Segment>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+	Literal(String),
+	Param(String),
+}
+
+/// Dispatches interactions to the handler registered for their command or `custom_id`
+#[derive(Clone, Default)]
+pub struct Router {
+	/// Along with a label for logging
+	routes: Vec<(Route, String, Handler)>,
+	fallback: Option<Handler>,
+	on_error: Option<ErrorHandler>,
+}
+
+fn boxed<F, Fut>(handler: F) -> Handler
+where
+	F: Fn(Context) -> Fut + Send + Sync + 'static,
+	Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+{
+	Arc::new(move |ctx| handler(ctx).boxed())
+}
+
+impl Router {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn route<F, Fut>(mut self, route: Route, label: &str, handler: F) -> Self
+	where
+		F: Fn(Context) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+	{
+		self.routes.push((route, label.to_owned(), boxed(handler)));
+		self
+	}
+
+	/// Slash command by its path, e.g. `/config set`. A handler for `/config` also
+	/// receives its subcommands that don't have a handler of their own
+	pub fn command<F, Fut>(self, path: &str, handler: F) -> Self
+	where
+		F: Fn(Context) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+	{
+		let path: Vec<_> = path
+			.trim_start_matches('/')
+			.split_whitespace()
+			.map(str::to_owned)
+			.collect();
+		let label = path.join(" ");
+		self.route(
+			Route::Command(CommandType::ChatInput, path),
+			&label,
+			handler,
+		)
+	}
+
+	/// Autocomplete of an option of a slash command, e.g. `("/music play", "query")`
+	pub fn autocomplete<F, Fut>(self, path: &str, option: &str, handler: F) -> Self
+	where
+		F: Fn(Context) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+	{
+		let path: Vec<_> = path
+			.trim_start_matches('/')
+			.split_whitespace()
+			.map(str::to_owned)
+			.collect();
+		let label = format!("{} ({})", path.join(" "), option);
+		self.route(
+			Route::Autocomplete(path, option.to_owned()),
+			&label,
+			handler,
+		)
+	}
+
+	/// Context menu command on a user
+	pub fn user_command<F, Fut>(self, name: &str, handler: F) -> Self
+	where
+		F: Fn(Context) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+	{
+		let route = Route::Command(CommandType::User, vec![name.to_owned()]);
+		self.route(route, name, handler)
+	}
+
+	/// Context menu command on a message
+	pub fn message_command<F, Fut>(self, name: &str, handler: F) -> Self
+	where
+		F: Fn(Context) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+	{
+		let route = Route::Command(CommandType::Message, vec![name.to_owned()]);
+		self.route(route, name, handler)
+	}
+
+	/// Button or select menu by its `custom_id`, `{name}` captures a parameter,
+	/// e.g. `ticket:close:{id}`
+	pub fn component<F, Fut>(self, pattern: &str, handler: F) -> Self
+	where
+		F: Fn(Context) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+	{
+		self.route(Route::Component(parse_pattern(pattern)), pattern, handler)
+	}
+
+	/// Submitted modal by its `custom_id`, with parameters like `Router::component`
+	pub fn modal<F, Fut>(self, pattern: &str, handler: F) -> Self
+	where
+		F: Fn(Context) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+	{
+		self.route(Route::Modal(parse_pattern(pattern)), pattern, handler)
+	}
+
+	/// Handler for interactions that no other handler matches
+	pub fn fallback<F, Fut>(mut self, handler: F) -> Self
+	where
+		F: Fn(Context) -> Fut + Send + Sync + 'static,
+		Fut: Future<Output = Result<(), HandlerError>> + Send + 'static,
+	{
+		self.fallback = Some(boxed(handler));
+		self
+	}
+
+	/// Called with the route and error when a handler fails, in addition to logging it
+	pub fn on_error<F>(mut self, on_error: F) -> Self
+	where
+		F: Fn(&str, &HandlerError) + Send + Sync + 'static,
+	{
+		self.on_error = Some(Arc::new(on_error));
+		self
+	}
+
+	/// Handler and captured parameters for an interaction
+	fn find(&self, kind: u64, data: &Value) -> Option<(&str, &Handler, HashMap<String, String>)> {
+		match kind {
+			APPLICATION_COMMAND => {
+				let command_type = data["type"].as_u64().unwrap_or(1);
+				let path = command_path(data);
+				self.routes
+					.iter()
+					.filter_map(|(route, label, handler)| match route {
+						Route::Command(t, p)
+							if *t as u64 == command_type && starts_with(&path, p) =>
+						{
+							Some((p.len(), label, handler))
+						}
+						_ => None,
+					})
+					.max_by_key(|(len, _, _)| *len)
+					.map(|(_, label, handler)| (label.as_str(), handler, HashMap::new()))
+			}
+			AUTOCOMPLETE => {
+				let path = command_path(data);
+				let mut options = CommandOptions::new(data);
+				while let Ok((_, nested)) = options.subcommand() {
+					options = nested;
+				}
+				let focused = options.focused()?;
+				self.routes
+					.iter()
+					.find_map(|(route, label, handler)| match route {
+						Route::Autocomplete(p, option) if path == *p && focused.name == option => {
+							Some((label.as_str(), handler, HashMap::new()))
+						}
+						_ => None,
+					})
+			}
+			MESSAGE_COMPONENT | MODAL_SUBMIT => {
+				let custom_id = data["custom_id"].as_str()?;
+				self.routes
+					.iter()
+					.find_map(|(route, label, handler)| match route {
+						Route::Component(pattern) if kind == MESSAGE_COMPONENT => {
+							Some((label.as_str(), handler, match_pattern(pattern, custom_id)?))
+						}
+						Route::Modal(pattern) if kind == MODAL_SUBMIT => {
+							Some((label.as_str(), handler, match_pattern(pattern, custom_id)?))
+						}
+						_ => None,
+					})
+			}
+			_ => None,
+		}
+	}
+
+	/// Run the matching handler, returns whether there was one. The returned future
+	/// doesn't borrow the guild, so it can be spawned
+	pub fn dispatch<S>(
+		&self,
+		guild: &Guild<S>,
+		interaction: RawInteraction,
+	) -> impl Future<Output = bool> + Send + 'static {
+		let found = self
+			.find(interaction.kind(), interaction.data())
+			.map(|(label, handler, params)| (label.to_owned(), handler.clone(), params))
+			.or_else(|| {
+				let fallback = self.fallback.clone()?;
+				Some(("fallback".to_owned(), fallback, HashMap::new()))
+			});
+		let on_error = self.on_error.clone();
+		let ctx = Context {
+			client: guild.client(),
+			guild_id: guild.id(),
+			application_id: guild.application_id(),
+			interaction,
+			params: HashMap::new(),
+		};
+
+		async move {
+			let (label, handler, params) = match found {
+				Some(found) => found,
+				None => {
+					debug!("No handler for interaction {}", ctx.interaction.id);
+					return false;
+				}
+			};
+			if let Err(e) = handler(Context { params, ..ctx }).await {
+				warn!("Interaction handler '{}' failed: {}", label, e);
+				if let Some(on_error) = on_error {
+					on_error(&label, &e);
+				}
+			}
+			true
+		}
+	}
+
+	pub fn spawn<S>(&self, guild: &Guild<S>, interaction: RawInteraction) -> JoinHandle<bool> {
+		tokio::spawn(self.dispatch(guild, interaction))
+	}
+}
+
+fn starts_with(path: &[&str], prefix: &[String]) -> bool {
+	path.len() >= prefix.len() && path.iter().zip(prefix).all(|(a, b)| a == b)
+}
+
+/// Command name followed by the names of the invoked subcommand group and subcommand
+fn command_path(data: &Value) -> Vec<&str> {
+	let mut path: Vec<_> = data["name"].as_str().into_iter().collect();
+	let mut options = &data["options"];
+	while let Some(option) = options
+		.as_array()
+		.and_then(|o| o.iter().find(|o| matches!(o["type"].as_u64(), Some(1 | 2))))
+	{
+		path.extend(option["name"].as_str());
+		options = &option["options"];
+	}
+	path
+}
+
+fn parse_pattern(pattern: &str) -> Vec<Segment> {
+	let mut segments = Vec::new();
+	let mut rest = pattern;
+	while let Some(start) = rest.find('{') {
+		let end = match rest[start..].find('}') {
+			Some(end) => start + end,
+			None => break,
+		};
+		if start > 0 {
+			segments.push(Segment::Literal(rest[..start].to_owned()));
+		}
+		segments.push(Segment::Param(rest[start + 1..end].to_owned()));
+		rest = &rest[end + 1..];
+	}
+	if !rest.is_empty() {
+		segments.push(Segment::Literal(rest.to_owned()));
+	}
+	segments
+}
+
+/// Parameters are non-empty and end at the next literal, or at the end of the id
+fn match_pattern(pattern: &[Segment], mut custom_id: &str) -> Option<HashMap<String, String>> {
+	let mut params = HashMap::new();
+	for (i, segment) in pattern.iter().enumerate() {
+		match segment {
+			Segment::Literal(literal) => custom_id = custom_id.strip_prefix(literal.as_str())?,
+			Segment::Param(name) => {
+				let end = match pattern.get(i + 1) {
+					Some(Segment::Literal(next)) => custom_id.find(next.as_str())?,
+					_ => custom_id.len(),
+				};
+				if end == 0 {
+					return None;
+				}
+				params.insert(name.clone(), custom_id[..end].to_owned());
+				custom_id = &custom_id[end..];
+			}
+		}
+	}
+	custom_id.is_empty().then_some(params)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	async fn noop(_: Context) -> Result<(), HandlerError> {
+		Ok(())
+	}
+
+	#[test]
+	fn component_pattern() {
+		let pattern = parse_pattern("ticket:close:{id}");
+		let params = match_pattern(&pattern, "ticket:close:1234").unwrap();
+		assert_eq!(params["id"], "1234");
+		assert!(match_pattern(&pattern, "ticket:close:").is_none());
+		assert!(match_pattern(&pattern, "ticket:open:1234").is_none());
+
+		let pattern = parse_pattern("vote:{poll}:{option}");
+		let params = match_pattern(&pattern, "vote:12:yes").unwrap();
+		assert_eq!((&*params["poll"], &*params["option"]), ("12", "yes"));
+	}
+
+	#[test]
+	fn autocomplete_route() {
+		let router = Router::new()
+			.autocomplete("/music play", "query", noop)
+			.command("/music play", noop);
+		let data = json!({
+			"name": "music",
+			"type": 1,
+			"options": [{
+				"name": "play",
+				"type": 1,
+				"options": [
+					{"name": "volume", "type": 4, "value": 50},
+					{"name": "query", "type": 3, "value": "never gon", "focused": true},
+				],
+			}],
+		});
+		assert_eq!(router.find(4, &data).unwrap().0, "music play (query)");
+		assert_eq!(router.find(2, &data).unwrap().0, "music play");
+
+		let focused = CommandOptions::new(&data["options"][0]).focused().unwrap();
+		assert_eq!(
+			(focused.name, focused.text().as_str()),
+			("query", "never gon")
+		);
+	}
+
+	#[test]
+	fn command_routes() {
+		let router = Router::new()
+			.command("/config", noop)
+			.command("/config set", noop)
+			.user_command("Report", noop)
+			.component("ticket:close:{id}", noop);
+
+		let data = json!({
+			"name": "config",
+			"type": 1,
+			"options": [{"name": "set", "type": 1, "options": []}],
+		});
+		assert_eq!(router.find(2, &data).unwrap().0, "config set");
+		let data = json!({
+			"name": "config",
+			"type": 1,
+			"options": [{"name": "show", "type": 1}],
+		});
+		assert_eq!(router.find(2, &data).unwrap().0, "config");
+		assert!(router
+			.find(2, &json!({"name": "Report", "type": 3}))
+			.is_none());
+		assert!(router
+			.find(5, &json!({"custom_id": "ticket:close:9"}))
+			.is_none());
+		assert_eq!(
+			router
+				.find(3, &json!({"custom_id": "ticket:close:9"}))
+				.unwrap()
+				.2["id"],
+			"9"
+		);
+	}
+}
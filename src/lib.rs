@@ -1,14 +1,20 @@
 pub use crate::client::{
-	ButtonComponent, Client, Error as ClientError, OptionalResult, RowComponent,
-	SelectMenuComponent, SelectOption,
+	ButtonComponent, CanReply, Client, ClientBuilder, CommandRegistry, EmbedBuilder,
+	Error as ClientError, ModalBuilder, OptionalResult, RowComponent, SelectMenuComponent,
+	SelectOption, TextInputComponent,
 };
 pub use crate::discord::{Builder, Discord};
-pub use crate::gateway::{Connector, Error as GatewayError, Gateway, GatewayEvent};
+pub use crate::gateway::{Connector, Error as GatewayError, Gateway, GatewayEvent, GatewayPayload};
 pub use crate::guild::Guild;
 pub use discord_types as types;
+use serde::de::{self, Deserializer};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fmt;
 
+// Lets the paths generated by `discord-async-derive` resolve within this crate
+extern crate self as discord_async;
+
 pub mod client;
 mod codec;
 mod discord;
@@ -87,19 +93,176 @@ impl fmt::Display for ProtocolError {
 pub struct ApiError {
 	code: u32,
 	message: String,
+	#[serde(default)]
+	errors: Option<FieldErrors>,
+	#[serde(skip)]
+	status: u16,
+	#[serde(skip)]
+	route: String,
 }
 
 impl ApiError {
+	pub const UNKNOWN_CHANNEL: u32 = 10003;
+	pub const UNKNOWN_GUILD: u32 = 10004;
+	pub const UNKNOWN_MEMBER: u32 = 10007;
+	pub const UNKNOWN_MESSAGE: u32 = 10008;
+	pub const UNKNOWN_ROLE: u32 = 10011;
+	pub const UNKNOWN_USER: u32 = 10013;
+	pub const UNKNOWN_INTERACTION: u32 = 10062;
+	pub const MISSING_ACCESS: u32 = 50001;
+	pub const CANNOT_SEND_TO_USER: u32 = 50007;
+	pub const MISSING_PERMISSIONS: u32 = 50013;
+	pub const INVALID_FORM_BODY: u32 = 50035;
+
 	pub fn code(&self) -> u32 {
 		self.code
 	}
+
 	pub fn message(&self) -> &str {
 		&self.message
 	}
+
+	/// Errors for the individual fields of the request body, if any
+	pub fn errors(&self) -> Option<&FieldErrors> {
+		self.errors.as_ref()
+	}
+
+	/// HTTP status code of the response
+	pub fn status(&self) -> u16 {
+		self.status
+	}
+
+	/// Method and path of the request, with ids and tokens masked,
+	/// e.g. `POST channels/1234/messages/:id` or `POST interactions/:id/:token/callback`
+	pub fn route(&self) -> &str {
+		&self.route
+	}
 }
 
 impl fmt::Display for ApiError {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "API error {}: {}", self.code, self.message)
+		write!(f, "API error {}: {}", self.code, self.message)?;
+		if let Some(errors) = &self.errors {
+			for (path, error) in errors.flatten() {
+				write!(f, "; {}: {}", path, error.message)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Tree of errors mirroring the structure of the request body.
+/// Array elements are keyed by their index, e.g. `embeds.0.description`
+#[derive(Clone, Debug, Default)]
+pub struct FieldErrors {
+	errors: Vec<FieldError>,
+	fields: BTreeMap<String, FieldErrors>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FieldError {
+	pub code: String,
+	pub message: String,
+}
+
+impl FieldErrors {
+	/// Errors of this field itself
+	pub fn errors(&self) -> &[FieldError] {
+		&self.errors
+	}
+
+	pub fn field(&self, name: &str) -> Option<&FieldErrors> {
+		self.fields.get(name)
+	}
+
+	pub fn fields(&self) -> impl Iterator<Item = (&str, &FieldErrors)> {
+		self.fields.iter().map(|(k, v)| (k.as_str(), v))
+	}
+
+	/// All errors in the tree together with the dot separated path of their field
+	pub fn flatten(&self) -> Vec<(String, &FieldError)> {
+		let mut out = Vec::new();
+		self.flatten_into(String::new(), &mut out);
+		out
+	}
+
+	fn flatten_into<'a>(&'a self, path: String, out: &mut Vec<(String, &'a FieldError)>) {
+		out.extend(self.errors.iter().map(|e| (path.clone(), e)));
+		for (name, field) in &self.fields {
+			let path = if path.is_empty() {
+				name.clone()
+			} else {
+				format!("{}.{}", path, name)
+			};
+			field.flatten_into(path, out);
+		}
+	}
+
+	fn from_value(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+		let mut tree = FieldErrors::default();
+		let map = match value {
+			serde_json::Value::Object(map) => map,
+			_ => return Ok(tree),
+		};
+		for (key, value) in map {
+			if key == "_errors" {
+				tree.errors = serde_json::from_value(value)?;
+			} else {
+				tree.fields.insert(key, FieldErrors::from_value(value)?);
+			}
+		}
+		Ok(tree)
+	}
+}
+
+impl<'de> Deserialize<'de> for FieldErrors {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let value = serde_json::Value::deserialize(deserializer)?;
+		FieldErrors::from_value(value).map_err(de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn api_error_fields() {
+		let body = r#"{
+			"code": 50035,
+			"message": "Invalid Form Body",
+			"errors": {
+				"content": {"_errors": [{"code": "BASE_TYPE_MAX_LENGTH", "message": "Must be 2000 or fewer in length."}]},
+				"embeds": {"0": {"title": {"_errors": [{"code": "BASE_TYPE_REQUIRED", "message": "This field is required"}]}}}
+			}
+		}"#;
+		let err: ApiError = serde_json::from_str(body).unwrap();
+		assert_eq!(err.code(), ApiError::INVALID_FORM_BODY);
+
+		let errors = err.errors().unwrap();
+		assert_eq!(
+			errors.field("content").unwrap().errors()[0].code,
+			"BASE_TYPE_MAX_LENGTH"
+		);
+		let flat: Vec<_> = errors
+			.flatten()
+			.into_iter()
+			.map(|(p, e)| (p, e.code.clone()))
+			.collect();
+		assert_eq!(
+			flat,
+			vec![
+				("content".to_owned(), "BASE_TYPE_MAX_LENGTH".to_owned()),
+				("embeds.0.title".to_owned(), "BASE_TYPE_REQUIRED".to_owned()),
+			]
+		);
+	}
+
+	#[test]
+	fn api_error_without_fields() {
+		let err: ApiError =
+			serde_json::from_str(r#"{"code": 10008, "message": "Unknown Message"}"#).unwrap();
+		assert_eq!(err.code(), ApiError::UNKNOWN_MESSAGE);
+		assert!(err.errors().is_none());
 	}
 }